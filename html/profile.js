@@ -0,0 +1,30 @@
+// Sorts the repo tables by the clicked column. The values to sort by are in
+// data attributes on the rows, so the displayed text doesn't matter.
+document.querySelectorAll("table.sortable").forEach((table) => {
+  const headers = table.querySelectorAll("th[data-key]");
+  headers.forEach((th) => {
+    th.addEventListener("click", () => {
+      const key = th.dataset.key;
+      const numeric = th.dataset.type === "number";
+      // Numbers and dates start out descending, names ascending.
+      const initial = numeric || key === "pushed" ? "descending" : "ascending";
+      const current = th.getAttribute("aria-sort");
+      const order = current ? (current === "ascending" ? "descending" : "ascending") : initial;
+      headers.forEach((h) => h.removeAttribute("aria-sort"));
+      th.setAttribute("aria-sort", order);
+
+      const tbody = table.tBodies[0];
+      const rows = Array.from(tbody.rows);
+      rows.sort((a, b) => {
+        const x = a.dataset[key];
+        const y = b.dataset[key];
+        const cmp = numeric ? Number(x) - Number(y) : x.localeCompare(y);
+        return order === "ascending" ? cmp : -cmp;
+      });
+      rows.forEach((row) => tbody.appendChild(row));
+    });
+  });
+});
+
+// Start out with the most recently pushed repos first.
+document.querySelectorAll('table.sortable th[data-key="pushed"]').forEach((th) => th.click());
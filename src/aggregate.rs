@@ -0,0 +1,1121 @@
+// Turns the normalized repos from the forges into the stats the templates
+// show. Everything here is a pure function of its arguments, the current
+// time included, so it can be tested without any forge.
+use anyhow::Result;
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc};
+use human_bytes::human_bytes;
+use itertools::Itertools;
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+};
+
+use crate::{
+    forge::{Language, Repo},
+    FeaturedCategory, FeaturedRepo,
+    formatters,
+    linguist::{self, LanguageType},
+    weighting::Weighting,
+    LanguageStat, LanguageTotal, LanguageYear, MonthlyPushes, MyRepo, PrimaryLanguage, RepoStarGrowth, RepoYear,
+    StarCount, StarGrowth, StarHistory, Timeline, TopRepos, UserAndRepoStats,
+};
+
+// Repository listing configuration
+const TOP_STARRED_REPOS: usize = 5;
+const TOP_FORKED_REPOS: usize = 5;
+const TOP_RECENT_REPOS: usize = 10;
+// How many years back a push makes a repo count as live.
+const RECENT_YEARS: i32 = 2;
+// How many months the push activity covers, the current one included.
+const PUSH_ACTIVITY_MONTHS: i32 = 12;
+// Longer repo descriptions are cut, at a word boundary if there is one.
+pub const DESCRIPTION_MAX_CHARS: usize = 100;
+// How many of the repos that gained the most stars lately are listed.
+const FASTEST_GROWING_REPOS: usize = 5;
+// Which year the languages of a repo count toward in the language evolution.
+pub const LANGUAGE_EVOLUTION_YEAR: YearOf = YearOf::LastPush;
+// pub const LANGUAGE_EVOLUTION_YEAR: YearOf = YearOf::Creation;
+
+// Language statistics configuration. The languages below the cutoff are
+// shown as one "Other" entry.
+pub const LANGUAGE_CUTOFF: LanguageCutoff = LanguageCutoff::MinPercentage(1.0);
+// pub const LANGUAGE_CUTOFF: LanguageCutoff = LanguageCutoff::Top(8);
+const OTHER_LANGUAGES: &str = "Other";
+const OTHER_LANGUAGES_COLOR: &str = "#ededed";
+
+// The linguist types of the languages that count toward the language stats.
+// Languages linguist doesn't know are always counted.
+const COUNTED_LANGUAGE_TYPES: &[LanguageType] = &[
+    LanguageType::Programming,
+//    LanguageType::Markup,
+];
+
+// Languages that are always counted as another one, on top of linguist's
+// aliases (which already fold e.g. "Perl 6" into Raku).
+const LANGUAGE_ALIASES: &[(&str, &str)] = &[
+//    ("Objective-C++", "C++"),
+];
+
+// Languages that are shown as one in the grouped views. Templates choose
+// between e.g. `top_all_time_languages` and `top_all_time_language_groups`.
+pub const LANGUAGE_GROUPS: &[LanguageGroup] = &[
+    LanguageGroup {
+        name: "Build/Scripts",
+        color: Some("#89e051"),
+        members: &["Shell", "Bash", "Makefile", "PowerShell", "Batchfile"],
+    },
+    // All markup, so uncomment this together with markup in
+    // COUNTED_LANGUAGE_TYPES.
+//    LanguageGroup {
+//        name: "Web",
+//        color: None,
+//        members: &["HTML", "CSS", "SCSS", "Less"],
+//    },
+];
+
+/// Which languages `top_languages` lists by themselves.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LanguageCutoff {
+    /// Those with at least this percentage of the total.
+    MinPercentage(f64),
+    /// The largest ones, up to this many.
+    Top(usize),
+}
+
+/// A year in the life of a repo.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum YearOf {
+    Creation,
+    LastPush,
+}
+
+impl std::fmt::Display for YearOf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            YearOf::Creation => "creation",
+            YearOf::LastPush => "last push",
+        })
+    }
+}
+
+/// A set of languages that the grouped views show as one.
+#[derive(Debug)]
+pub struct LanguageGroup {
+    pub name: &'static str,
+    /// The group's color. Without one, the group takes the color of its
+    /// largest member.
+    pub color: Option<&'static str>,
+    pub members: &'static [&'static str],
+}
+
+/// A repo to show off, with a line about it. Repos in the same `category`
+/// are listed together, in the order of the config.
+#[derive(Debug, Clone, Copy)]
+pub struct FeaturedConfig<'a> {
+    pub full_name: &'a str,
+    pub category: &'a str,
+    pub blurb: &'a str,
+}
+
+/// Builds the stats from the normalized `repos` of all forges. Repos pushed
+/// to within `RECENT_YEARS` of `now` count as live, and `weighting` decides
+/// the weighted language totals.
+pub fn collect_user_repo_stats(repos: &[Repo], now: DateTime<Utc>, weighting: Weighting) -> Result<UserAndRepoStats> {
+    let cutoff = recent_cutoff(now);
+    let today = now.naive_utc().date();
+    let mut stats = UserAndRepoStats {
+        language_weighting: weighting.to_string(),
+        languages_weighted: weighting != Weighting::Bytes,
+        languages_by_year_of: LANGUAGE_EVOLUTION_YEAR.to_string(),
+        ..Default::default()
+    };
+    // Only so featured repos that were archived can be told from missing ones.
+    stats.archived_repos = repos
+        .iter()
+        .filter(|r| r.is_archived && is_own(r) && !r.is_private)
+        .map(|r| r.full_name.clone())
+        .collect();
+    // The years repos were created and last pushed to in.
+    let (mut created_years, mut pushed_years) = (vec![], vec![]);
+    for repo in repos.iter().filter(|r| is_listed(r)) {
+        stats.total_repos += 1;
+        if !is_own(repo) {
+            stats.forked_repos += 1;
+            continue;
+        }
+
+        stats.owned_repos += 1;
+        stats.total_stars += repo.stargazer_count;
+        stats.total_forks += repo.fork_count;
+        // Bot commits and pushes to other branches don't make a repo recent.
+        let pushed_at = match repo.last_commit_at.as_ref().or(repo.pushed_at.as_ref()) {
+            Some(pushed_at) => Some(DateTime::parse_from_rfc3339(pushed_at)?.with_timezone(&Utc)),
+            None => None,
+        };
+        let created_date = match &repo.created_at {
+            Some(created_at) => Some(DateTime::parse_from_rfc3339(created_at)?.with_timezone(&Utc).naive_utc().date()),
+            None => None,
+        };
+        created_years.extend(created_date.map(|d| d.year()));
+        pushed_years.extend(pushed_at.map(|p| p.year()));
+        let weight = |bytes| weighting.factor(repo, pushed_at, bytes, now);
+        collect_language_stats(&mut stats.all_time_languages, &repo.full_name, &repo.languages, weight);
+        let year = match LANGUAGE_EVOLUTION_YEAR {
+            YearOf::Creation => created_date.map(|d| d.year()),
+            YearOf::LastPush => pushed_at.map(|p| p.year()),
+        };
+        if let Some(year) = year {
+            let languages = stats.languages_by_year.entry(year).or_default();
+            collect_language_stats(languages, &repo.full_name, &repo.languages, weight);
+        }
+
+        let my_repo = MyRepo {
+            full_name: repo.full_name.clone(),
+            url: repo.url.clone(),
+            fork_count: repo.fork_count,
+            stargazer_count: repo.stargazer_count,
+            pushed_date: pushed_at.map(|p| p.format("%Y-%m-%d").to_string()).unwrap_or_default(),
+            created_date: created_date.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default(),
+            age_years: created_date.map_or(0, |d| whole_years(d, today)),
+            description: repo
+                .description
+                .as_deref()
+                .and_then(|d| sanitize_description(d, DESCRIPTION_MAX_CHARS)),
+            homepage_url: repo.homepage_url.clone(),
+            primary_language: primary_language(repo),
+            open_graph_image_url: repo.open_graph_image_url.clone(),
+        };
+        if pushed_at.is_none_or(|p| p < cutoff) {
+            stats.dormant_repos.push(my_repo);
+            continue;
+        }
+        collect_language_stats(&mut stats.recent_languages, &repo.full_name, &repo.languages, weight);
+        stats.live_repos += 1;
+        stats.repos.push(my_repo);
+    }
+    stats.repos_by_year = repos_by_year(&created_years, &pushed_years, now.year());
+    Ok(stats)
+}
+
+/// `description` on one line, without control characters, and cut to at most
+/// `max_chars` with an ellipsis. `None` when nothing is left. Markdown
+/// syntax in it is escaped when rendering, like all values.
+pub fn sanitize_description(description: &str, max_chars: usize) -> Option<String> {
+    let text = description
+        .split(|c: char| c.is_whitespace() || c.is_control())
+        .filter(|w| !w.is_empty())
+        .join(" ");
+    if text.is_empty() {
+        return None;
+    }
+    if text.chars().count() <= max_chars {
+        return Some(text);
+    }
+    let keep = max_chars.saturating_sub(1);
+    let cut: String = text.chars().take(keep).collect();
+    // Rather cut at a space, unless that throws away most of it.
+    let at_space = text.chars().nth(keep) == Some(' ');
+    let cut = match cut.rfind(' ') {
+        Some(i) if !at_space && i >= cut.len() / 2 => &cut[..i],
+        _ => cut.as_str(),
+    };
+    Some(format!("{}…", cut.trim_end_matches(|c: char| c.is_whitespace() || c.is_ascii_punctuation())))
+}
+
+// The forge's main language of `repo`, else its largest one.
+fn primary_language(repo: &Repo) -> Option<PrimaryLanguage> {
+    let name = repo
+        .primary_language
+        .as_deref()
+        .or_else(|| repo.languages.iter().max_by_key(|l| l.bytes).map(|l| l.name.as_str()))?;
+    let color = repo.languages.iter().find(|l| l.name == name).and_then(|l| l.color.as_deref());
+    Some(PrimaryLanguage {
+        name: name.to_string(),
+        color: language_color(name, color).to_string(),
+    })
+}
+
+// Every year from the first one in `created` or `pushed` up to
+// `current_year`, with how many repos were created and last pushed to in it.
+fn repos_by_year(created: &[i32], pushed: &[i32], current_year: i32) -> Vec<RepoYear> {
+    let Some(&first) = created.iter().chain(pushed).min() else {
+        return vec![];
+    };
+    let count = |years: &[i32], year| years.iter().filter(|y| **y == year).count() as i64;
+    (first..=current_year)
+        .map(|year| RepoYear {
+            year,
+            created: count(created, year),
+            pushed: count(pushed, year),
+        })
+        .collect()
+}
+
+/// How many full years lie between `from` and `to`, as in someone's age.
+pub fn whole_years(from: NaiveDate, to: NaiveDate) -> i64 {
+    let years = (to.year() - from.year()) as i64;
+    if (to.month(), to.day()) < (from.month(), from.day()) {
+        years - 1
+    } else {
+        years
+    }
+}
+
+/// The account age as of `now`, and the live repo that was created first.
+pub fn timeline(stats: &UserAndRepoStats, now: DateTime<Utc>) -> Timeline<'_> {
+    let account_created = formatters::parse_date(&stats.created_at);
+    let oldest_active_repo = stats
+        .repos
+        .iter()
+        .filter(|r| !r.created_date.is_empty())
+        .min_by(|a, b| a.created_date.cmp(&b.created_date).then_with(|| a.full_name.cmp(&b.full_name)));
+    Timeline {
+        account_created_date: account_created.map(|d| d.format("%Y-%m-%d").to_string()),
+        account_age_years: account_created.map(|d| whole_years(d, now.naive_utc().date())),
+        oldest_active_repo,
+    }
+}
+
+// Archived, disabled, empty and private repos aren't counted at all.
+fn is_listed(repo: &Repo) -> bool {
+    !(repo.is_archived || repo.is_disabled || repo.is_empty || repo.is_private)
+}
+
+// A mirror on another forge that wasn't merged with one of our repos is
+// someone else's project, same as a fork.
+fn is_own(repo: &Repo) -> bool {
+    !(repo.is_fork || repo.is_mirror)
+}
+
+/// The oldest push that still counts as recent: the same date and time
+/// `RECENT_YEARS` before `now`, or Feb 28 when `now` is a leap day.
+pub fn recent_cutoff(now: DateTime<Utc>) -> DateTime<Utc> {
+    // chrono::Duration only goes up to weeks, and two years aren't a fixed
+    // number of weeks.
+    let year = now.year() - RECENT_YEARS;
+    let date = NaiveDate::from_ymd_opt(year, now.month(), now.day())
+        .or_else(|| NaiveDate::from_ymd_opt(year, now.month(), now.day() - 1))
+        .expect("only Feb 29 can be missing in another year");
+    Utc.from_utc_datetime(&date.and_time(now.time()))
+}
+
+const REPOS_TO_IGNORE_FOR_LANGUAGE_STATS: &[&str] = &[
+    // The presentations repo has a ton of HTML and JS I didn't write
+    // and this distorts the stats.
+//    "autarch/presentations",
+    // The mason book is HTML, but it's just the HTMl from the old dynamic
+    // site which I crawled, so it's not interesting for these stats.
+//    "autarch/masonbook.houseabsolute.com",
+];
+
+/// Adds the bytes of `languages` to the per-language totals in `stats`.
+/// `weight` gets the counted bytes of the repo, and returns what each of them
+/// is worth in the weighted totals.
+pub fn collect_language_stats(
+    stats: &mut HashMap<String, LanguageTotal>,
+    repo_name: &str,
+    languages: &[Language],
+    weight: impl Fn(i64) -> f64,
+) {
+    if languages.is_empty() || REPOS_TO_IGNORE_FOR_LANGUAGE_STATS.contains(&repo_name) {
+        return;
+    }
+    let mut counted = vec![];
+    for language in languages {
+        #[allow(clippy::match_single_binding)]
+        let lang = match (repo_name, language.name.as_str()) {
+            // This is really XS, not C (although arguably, XS is just C).
+            //("houseabsolute/File-LibMagic", "C") => "XS",
+            (_, l) => l,
+        };
+        let lang = LANGUAGE_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == lang)
+            .map_or(lang, |(_, name)| name);
+        let info = linguist::lookup(lang);
+        if let Some(info) = info.filter(|l| !COUNTED_LANGUAGE_TYPES.contains(&l.kind)) {
+            tracing::trace!("Not counting {} ({:?}) in {}", info.name, info.kind, repo_name);
+            continue;
+        }
+        let lang = info.map_or(lang, |l| l.name);
+
+        // The tidyall repo has a bunch of PHP and JS checked in for
+        // testing, but none of it is code I've written or maintained.
+        /*
+        if repo_name == "houseabsolute/perl-code-tidyall" && lang != "Perl" {
+            continue;
+        }
+        */
+        counted.push((lang, language_color(lang, language.color.as_deref()), language.bytes));
+    }
+
+    let factor = weight(counted.iter().map(|(_, _, bytes)| bytes).sum());
+    for (lang, color, bytes) in counted {
+        let total = stats.entry(lang.to_string()).or_insert_with(|| LanguageTotal {
+            color: color.to_string(),
+            ..Default::default()
+        });
+        total.bytes += bytes;
+        total.weight += bytes as f64 * factor;
+    }
+}
+
+/// The color of `lang`: the one the forge reported, else linguist's, else
+/// gray.
+pub fn language_color<'a>(lang: &str, color: Option<&'a str>) -> &'a str {
+    match color.or_else(|| linguist::lookup(lang).and_then(|l| l.color)) {
+        Some(c) => c,
+        None => {
+            tracing::warn!("No color defined for language '{}'; using default gray", lang);
+            "#808080" // Default gray color
+        }
+    }
+}
+
+/// The repos of `config`, by category, with their blurbs. Dormant repos can
+/// be featured too. Entries that don't match an owned repo are left out with
+/// a warning, and ones that only match by their short name or case, as after
+/// a transfer to another owner, are kept with one. Repos renamed otherwise
+/// can't be told from deleted ones, as only the current names are fetched.
+pub fn featured_repos<'a>(config: &[FeaturedConfig<'a>], stats: &'a UserAndRepoStats) -> Vec<FeaturedCategory<'a>> {
+    let owned = || stats.repos.iter().chain(&stats.dormant_repos);
+    let mut categories: Vec<FeaturedCategory<'a>> = vec![];
+    for entry in config {
+        let repo = owned().find(|r| r.full_name == entry.full_name).or_else(|| {
+            let short_name = |name: &str| name.rsplit('/').next().unwrap_or(name).to_lowercase();
+            let moved = owned().find(|r| short_name(&r.full_name) == short_name(entry.full_name))?;
+            tracing::warn!("Featured repo {} is now {}; please update the config", entry.full_name, moved.full_name);
+            Some(moved)
+        });
+        let Some(repo) = repo else {
+            if stats.archived_repos.iter().any(|r| r.eq_ignore_ascii_case(entry.full_name)) {
+                tracing::warn!("Featured repo {} is archived; leaving it out", entry.full_name);
+            } else {
+                tracing::warn!(
+                    "Featured repo {} is missing or private, or was renamed (then the config needs the new name); leaving it out",
+                    entry.full_name
+                );
+            }
+            continue;
+        };
+        let featured = FeaturedRepo {
+            blurb: entry.blurb,
+            repo,
+        };
+        match categories.iter_mut().find(|c| c.name == entry.category) {
+            Some(category) => category.repos.push(featured),
+            None => categories.push(FeaturedCategory {
+                name: entry.category,
+                repos: vec![featured],
+            }),
+        }
+    }
+    categories
+}
+
+/// The most recently pushed, most starred and most forked of `repos`.
+pub fn top_repos(repos: &[MyRepo]) -> TopRepos<'_> {
+    let most_forked = repos
+        .iter()
+        .filter(|r| r.fork_count > 0)
+        .sorted_by(|a, b| b.fork_count.cmp(&a.fork_count))
+        .take(TOP_FORKED_REPOS)
+        .collect::<Vec<_>>();
+    let most_starred = top_n(repos, TOP_STARRED_REPOS, |a, b| b.stargazer_count.cmp(&a.stargazer_count));
+    let most_recent = top_n(repos, TOP_RECENT_REPOS, |a, b| b.pushed_date.cmp(&a.pushed_date));
+    TopRepos {
+        most_forked,
+        most_recent,
+        most_starred,
+    }
+}
+
+/// The top languages of every year in `languages_by_year`, oldest first.
+/// Years without any counted language are left out.
+pub fn language_evolution(
+    languages_by_year: &BTreeMap<i32, HashMap<String, LanguageTotal>>,
+    cutoff: LanguageCutoff,
+) -> Vec<LanguageYear<'_>> {
+    languages_by_year
+        .iter()
+        .filter(|(_, languages)| !languages.is_empty())
+        .map(|(year, languages)| LanguageYear {
+            year: *year,
+            languages: top_languages(languages, cutoff),
+        })
+        .collect()
+}
+
+/// How many of `repos` were last pushed to in each of the
+/// `PUSH_ACTIVITY_MONTHS` months up to the one of `now`, oldest first.
+pub fn push_activity(repos: &[MyRepo], now: DateTime<Utc>) -> Vec<MonthlyPushes> {
+    let current = now.year() * 12 + now.month0() as i32;
+    let mut months: Vec<MonthlyPushes> = (current - PUSH_ACTIVITY_MONTHS + 1..=current)
+        .map(|m| MonthlyPushes {
+            month: format!("{:04}-{:02}", m / 12, m % 12 + 1),
+            repos: 0,
+        })
+        .collect();
+    for repo in repos {
+        if let Some(month) = months.iter_mut().find(|m| repo.pushed_date.starts_with(&m.month)) {
+            month.repos += 1;
+        }
+    }
+    months
+}
+
+/// Sums up `history`: the star counts over time, the stars gained in the
+/// last 30 and 365 days up to `now`, and the repos that gained the most.
+pub fn star_growth(history: &StarHistory, now: DateTime<Utc>) -> StarGrowth<'_> {
+    let today = now.naive_utc().date();
+    let since = |days| (today - chrono::Duration::days(days)).format("%Y-%m-%d").to_string();
+    let (month_ago, year_ago) = (since(30), since(365));
+    let gained = |days: &[&str], since: &str| days.iter().filter(|d| **d > since).count() as i64;
+
+    let all_days: Vec<&str> = history.repos.iter().flat_map(|r| r.starred_on.iter().map(String::as_str)).sorted().collect();
+    let repos: Vec<_> = history
+        .repos
+        .iter()
+        .filter(|r| !r.starred_on.is_empty())
+        .map(|r| {
+            let days: Vec<&str> = r.starred_on.iter().map(String::as_str).collect();
+            RepoStarGrowth {
+                full_name: &r.full_name,
+                url: &r.url,
+                stars: days.len() as i64,
+                gained_last_30_days: gained(&days, &month_ago),
+                gained_last_365_days: gained(&days, &year_ago),
+                series: star_series(&days),
+            }
+        })
+        .sorted_by(|a, b| b.stars.cmp(&a.stars).then_with(|| a.full_name.cmp(b.full_name)))
+        .collect();
+    let fastest_growing = repos
+        .iter()
+        .filter(|r| r.gained_last_365_days > 0)
+        .sorted_by(|a, b| {
+            (b.gained_last_30_days, b.gained_last_365_days)
+                .cmp(&(a.gained_last_30_days, a.gained_last_365_days))
+                .then_with(|| a.full_name.cmp(b.full_name))
+        })
+        .take(FASTEST_GROWING_REPOS)
+        .cloned()
+        .collect();
+    StarGrowth {
+        total: star_series(&all_days),
+        gained_last_30_days: gained(&all_days, &month_ago),
+        gained_last_365_days: gained(&all_days, &year_ago),
+        repos,
+        fastest_growing,
+    }
+}
+
+// The running star count on each of the sorted `days`, once per day.
+fn star_series(days: &[&str]) -> Vec<StarCount> {
+    let mut series: Vec<StarCount> = vec![];
+    for (i, day) in days.iter().enumerate() {
+        let stars = i as i64 + 1;
+        match series.last_mut() {
+            Some(last) if last.date == *day => last.stars = stars,
+            _ => series.push(StarCount {
+                date: day.to_string(),
+                stars,
+            }),
+        }
+    }
+    series
+}
+
+fn top_n<S>(repos: &[MyRepo], take: usize, sorter: S) -> Vec<&MyRepo>
+where
+    S: FnMut(&&MyRepo, &&MyRepo) -> Ordering,
+{
+    repos
+        .iter()
+        .sorted_by(sorter)
+        .take(take)
+        .collect::<Vec<_>>()
+}
+
+/// The languages in `languages` above `cutoff`, largest first, followed by an
+/// "Other" entry for the rest, unless that rounds to 0% both weighted and raw.
+/// The percentages are rounded so that they add up to exactly 100.
+pub fn top_languages(languages: &HashMap<String, LanguageTotal>, cutoff: LanguageCutoff) -> Vec<LanguageStat<'_>> {
+    let total_weight: f64 = languages.values().map(|l| l.weight).sum();
+    let total_size: i64 = languages.values().map(|l| l.bytes).sum();
+    let share = |part: f64, total: f64| if total > 0.0 { part / total * 100.0 } else { 0.0 };
+
+    let mut sorted: Vec<(&str, &LanguageTotal)> = languages.iter().map(|(k, v)| (k.as_str(), v)).collect();
+    sorted.sort_by(|a, b| b.1.weight.total_cmp(&a.1.weight).then(a.0.cmp(b.0)));
+    let shown = match cutoff {
+        LanguageCutoff::MinPercentage(min) => sorted
+            .iter()
+            .take_while(|(_, l)| share(l.weight, total_weight) >= min)
+            .count(),
+        LanguageCutoff::Top(n) => n.min(sorted.len()),
+    };
+    let (shown, rest) = sorted.split_at(shown);
+
+    // (name, color, weight, bytes, members)
+    let mut entries: Vec<(&str, &str, f64, i64, Vec<&str>)> = shown
+        .iter()
+        .map(|(name, l)| (*name, l.color.as_str(), l.weight, l.bytes, vec![]))
+        .collect();
+    if !rest.is_empty() {
+        tracing::debug!("Showing {} languages as {OTHER_LANGUAGES}", rest.len());
+        entries.push((
+            OTHER_LANGUAGES,
+            OTHER_LANGUAGES_COLOR,
+            rest.iter().map(|(_, l)| l.weight).sum(),
+            rest.iter().map(|(_, l)| l.bytes).sum(),
+            rest.iter().map(|(name, _)| *name).collect(),
+        ));
+    }
+
+    let percentages = largest_remainder(&entries.iter().map(|e| share(e.2, total_weight)).collect::<Vec<_>>());
+    let raw_percentages =
+        largest_remainder(&entries.iter().map(|e| share(e.3 as f64, total_size as f64)).collect::<Vec<_>>());
+    let mut stats: Vec<_> = entries
+        .into_iter()
+        .zip(percentages.into_iter().zip(raw_percentages))
+        .map(|((name, color, _, bytes, members), (percentage, raw_percentage))| LanguageStat {
+            name,
+            color,
+            percentage,
+            raw_percentage,
+            bytes: human_bytes(bytes as f64),
+            members,
+        })
+        .collect();
+    if !rest.is_empty() && stats.last().is_some_and(|other| other.percentage == 0 && other.raw_percentage == 0) {
+        stats.pop();
+    }
+    stats
+}
+
+// Rounds `shares` that add up to 100 so the rounded ones do too: everything
+// is rounded down, and the points that are missing then go to the shares
+// with the largest remainders (the first one on ties).
+fn largest_remainder(shares: &[f64]) -> Vec<i64> {
+    let mut rounded: Vec<i64> = shares.iter().map(|s| s.floor() as i64).collect();
+    let total = shares.iter().sum::<f64>().round() as i64;
+    let missing = (total - rounded.iter().sum::<i64>()).max(0) as usize;
+    let by_remainder = (0..shares.len()).sorted_by(|&a, &b| {
+        let remainder = |i: usize| shares[i] - shares[i].floor();
+        remainder(b).total_cmp(&remainder(a)).then(a.cmp(&b))
+    });
+    for i in by_remainder.take(missing) {
+        rounded[i] += 1;
+    }
+    rounded
+}
+
+/// Merges the members of each of `groups` in `languages` into one entry named
+/// after the group. Languages outside of all groups are kept as they are.
+pub fn group_languages(
+    languages: &HashMap<String, LanguageTotal>,
+    groups: &[LanguageGroup],
+) -> HashMap<String, LanguageTotal> {
+    let mut grouped: HashMap<String, LanguageTotal> = HashMap::new();
+    // The largest member of each group so far, for groups without a color.
+    let mut largest: HashMap<&str, (i64, &str)> = HashMap::new();
+    for (name, language) in languages {
+        let Some(group) = groups.iter().find(|g| g.members.contains(&name.as_str())) else {
+            let entry = grouped.entry(name.clone()).or_insert_with(|| LanguageTotal {
+                color: language.color.clone(),
+                ..Default::default()
+            });
+            entry.bytes += language.bytes;
+            entry.weight += language.weight;
+            continue;
+        };
+        let entry = grouped.entry(group.name.to_string()).or_default();
+        entry.bytes += language.bytes;
+        entry.weight += language.weight;
+        match group.color {
+            Some(c) => entry.color = c.to_string(),
+            None => {
+                // Ties go to the first name, so the color doesn't depend on
+                // the map's order.
+                let size = language.bytes;
+                let is_largest = largest
+                    .get(group.name)
+                    .is_none_or(|&(s, n)| size > s || (size == s && name.as_str() < n));
+                if is_largest {
+                    largest.insert(group.name, (size, name));
+                    entry.color = language.color.clone();
+                }
+            }
+        }
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RepoStars;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2024-06-15T12:00:00Z").unwrap().with_timezone(&Utc)
+    }
+
+    fn repo(pushed_at: Option<&str>) -> Repo {
+        Repo {
+            full_name: "AndreasOM/repo".to_string(),
+            url: "https://github.com/AndreasOM/repo".to_string(),
+            stargazer_count: 3,
+            fork_count: 1,
+            created_at: Some("2019-08-20T10:00:00Z".to_string()),
+            pushed_at: pushed_at.map(str::to_string),
+            languages: vec![Language {
+                name: "Rust".to_string(),
+                color: Some("#dea584".to_string()),
+                bytes: 100,
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn classifies_repos() {
+        let recent = Some("2024-01-01T00:00:00Z");
+        // (case, repo, total, owned, forked, live)
+        let cases: &[(&str, Repo, i64, i64, i64, i64)] = &[
+            ("own and recent", repo(recent), 1, 1, 0, 1),
+            ("fork", Repo { is_fork: true, ..repo(recent) }, 1, 0, 1, 0),
+            ("unmerged mirror", Repo { is_mirror: true, ..repo(recent) }, 1, 0, 1, 0),
+            ("archived", Repo { is_archived: true, ..repo(recent) }, 0, 0, 0, 0),
+            ("disabled", Repo { is_disabled: true, ..repo(recent) }, 0, 0, 0, 0),
+            ("empty", Repo { is_empty: true, ..repo(recent) }, 0, 0, 0, 0),
+            ("private", Repo { is_private: true, ..repo(recent) }, 0, 0, 0, 0),
+            ("archived fork", Repo { is_archived: true, is_fork: true, ..repo(recent) }, 0, 0, 0, 0),
+            ("never pushed", repo(None), 1, 1, 0, 0),
+            ("pushed at the cutoff", repo(Some("2022-06-15T12:00:00Z")), 1, 1, 0, 1),
+            ("pushed just before the cutoff", repo(Some("2022-06-15T11:59:59Z")), 1, 1, 0, 0),
+            ("pushed with an offset", repo(Some("2022-06-15T13:00:00+01:00")), 1, 1, 0, 1),
+        ];
+        for (case, repo, total, owned, forked, live) in cases {
+            let stats = collect_user_repo_stats(std::slice::from_ref(repo), now(), Weighting::Bytes).unwrap();
+            assert_eq!(
+                (stats.total_repos, stats.owned_repos, stats.forked_repos, stats.live_repos),
+                (*total, *owned, *forked, *live),
+                "{case}"
+            );
+            assert_eq!(stats.repos.len() as i64, *live, "{case}");
+            let stars = if *owned > 0 { 3 } else { 0 };
+            assert_eq!(stats.total_stars, stars, "{case}");
+            assert_eq!(stats.all_time_languages.contains_key("Rust"), *owned > 0, "{case}");
+            assert_eq!(stats.recent_languages.contains_key("Rust"), *live > 0, "{case}");
+        }
+    }
+
+    #[test]
+    fn sums_languages_across_repos() {
+        let repos = [
+            repo(Some("2024-01-01T00:00:00Z")),
+            Repo {
+                full_name: "AndreasOM/old".to_string(),
+                ..repo(Some("2020-01-01T00:00:00Z"))
+            },
+        ];
+        let stats = collect_user_repo_stats(&repos, now(), Weighting::Bytes).unwrap();
+        assert_eq!(stats.all_time_languages["Rust"], total("#dea584", 200));
+        assert_eq!(stats.recent_languages["Rust"], total("#dea584", 100));
+        assert_eq!(stats.repos[0].pushed_date, "2024-01-01");
+    }
+
+    #[test]
+    fn invalid_push_dates_are_errors() {
+        assert!(collect_user_repo_stats(&[repo(Some("yesterday"))], now(), Weighting::Bytes).is_err());
+    }
+
+    #[test]
+    fn last_commit_is_preferred_over_last_push() {
+        let bumped = |last_commit_at: &str| Repo {
+            last_commit_at: Some(last_commit_at.to_string()),
+            ..repo(Some("2024-06-01T00:00:00Z"))
+        };
+        let stats = collect_user_repo_stats(&[bumped("2020-01-01T00:00:00Z")], now(), Weighting::Bytes).unwrap();
+        assert_eq!(stats.live_repos, 0);
+        assert!(!stats.recent_languages.contains_key("Rust"));
+        let stats = collect_user_repo_stats(&[bumped("2023-03-04T00:00:00Z")], now(), Weighting::Bytes).unwrap();
+        assert_eq!(stats.repos[0].pushed_date, "2023-03-04");
+    }
+
+    #[test]
+    fn timeline_by_year() {
+        let created = |created_at: &str, pushed_at| Repo {
+            created_at: Some(created_at.to_string()),
+            ..repo(Some(pushed_at))
+        };
+        let repos = [
+            Repo { full_name: "AndreasOM/young".to_string(), ..created("2023-01-10T00:00:00Z", "2024-05-01T00:00:00Z") },
+            Repo { full_name: "AndreasOM/old".to_string(), ..created("2019-06-16T00:00:00Z", "2024-01-01T00:00:00Z") },
+            Repo { full_name: "AndreasOM/dead".to_string(), ..created("2018-03-01T00:00:00Z", "2020-02-01T00:00:00Z") },
+            Repo { is_fork: true, ..created("2017-01-01T00:00:00Z", "2017-01-01T00:00:00Z") },
+        ];
+        let mut stats = collect_user_repo_stats(&repos, now(), Weighting::Bytes).unwrap();
+        let years: Vec<_> = stats.repos_by_year.iter().map(|y| (y.year, y.created, y.pushed)).collect();
+        assert_eq!(
+            years,
+            [(2018, 1, 0), (2019, 1, 0), (2020, 0, 1), (2021, 0, 0), (2022, 0, 0), (2023, 1, 0), (2024, 0, 2)]
+        );
+        let ages: Vec<_> = stats.repos.iter().map(|r| (r.created_date.as_str(), r.age_years)).collect();
+        assert_eq!(ages, [("2023-01-10", 1), ("2019-06-16", 4)]);
+
+        stats.created_at = "2010-06-15T13:00:00Z".to_string();
+        let timeline = timeline(&stats, now());
+        assert_eq!(timeline.account_created_date.as_deref(), Some("2010-06-15"));
+        assert_eq!(timeline.account_age_years, Some(14));
+        assert_eq!(timeline.oldest_active_repo.unwrap().full_name, "AndreasOM/old");
+
+        stats.created_at = String::new();
+        assert_eq!(super::timeline(&stats, now()).account_age_years, None);
+    }
+
+    #[test]
+    fn languages_by_year_of_last_push() {
+        let language = |name: &str, bytes| Language { name: name.to_string(), color: None, bytes };
+        let repos = [
+            Repo { languages: vec![language("C++", 300)], ..repo(Some("2019-03-01T00:00:00Z")) },
+            Repo { languages: vec![language("C++", 100), language("Rust", 100)], ..repo(Some("2019-11-01T00:00:00Z")) },
+            Repo { languages: vec![language("Rust", 500)], ..repo(Some("2024-01-01T00:00:00Z")) },
+            Repo { languages: vec![language("Rust", 500)], ..repo(None) },
+        ];
+        let stats = collect_user_repo_stats(&repos, now(), Weighting::Bytes).unwrap();
+        assert_eq!(stats.languages_by_year_of, "last push");
+        assert_eq!(stats.languages_by_year.keys().collect::<Vec<_>>(), [&2019, &2024]);
+        assert_eq!(stats.languages_by_year[&2019]["C++"].bytes, 400);
+
+        let evolution: Vec<_> = language_evolution(&stats.languages_by_year, LanguageCutoff::Top(5))
+            .iter()
+            .map(|y| (y.year, y.languages.iter().map(|l| (l.name, l.percentage)).collect::<Vec<_>>()))
+            .collect();
+        assert_eq!(evolution, [(2019, vec![("C++", 80), ("Rust", 20)]), (2024, vec![("Rust", 100)])]);
+    }
+
+    #[test]
+    fn descriptions_are_cleaned_up_and_cut() {
+        assert_eq!(sanitize_description("  Tools for\r\nmaking\tgames ", 100).as_deref(), Some("Tools for making games"));
+        assert_eq!(sanitize_description(" \n ", 100), None);
+        assert_eq!(sanitize_description("bell\u{7}ring", 100).as_deref(), Some("bell ring"));
+        assert_eq!(sanitize_description("A pixel art editor, in Rust", 20).as_deref(), Some("A pixel art editor…"));
+        assert_eq!(sanitize_description("Supercalifragilistic", 10).as_deref(), Some("Supercali…"));
+        assert_eq!(sanitize_description("Short enough", 12).as_deref(), Some("Short enough"));
+    }
+
+    #[test]
+    fn repo_details_are_listed() {
+        let language = |name: &str, bytes| Language { name: name.to_string(), color: None, bytes };
+        let repos = [
+            Repo {
+                full_name: "AndreasOM/forge".to_string(),
+                description: Some("Tools for\nmaking games".to_string()),
+                homepage_url: Some("https://example.com".to_string()),
+                primary_language: Some("C++".to_string()),
+                ..repo(Some("2024-01-01T00:00:00Z"))
+            },
+            Repo {
+                full_name: "AndreasOM/mixed".to_string(),
+                languages: vec![language("Lua", 10), language("Rust", 500)],
+                ..repo(Some("2024-01-01T00:00:00Z"))
+            },
+            Repo { languages: vec![], ..repo(Some("2024-01-01T00:00:00Z")) },
+        ];
+        let stats = collect_user_repo_stats(&repos, now(), Weighting::Bytes).unwrap();
+        let forge = &stats.repos[0];
+        assert_eq!(forge.description.as_deref(), Some("Tools for making games"));
+        assert_eq!(forge.homepage_url.as_deref(), Some("https://example.com"));
+        assert_eq!(
+            forge.primary_language,
+            Some(PrimaryLanguage { name: "C++".to_string(), color: "#f34b7d".to_string() })
+        );
+        assert_eq!(stats.repos[1].primary_language.as_ref().unwrap().name, "Rust");
+        assert_eq!(stats.repos[2].primary_language, None);
+    }
+
+    #[test]
+    fn featured_repos_by_category() {
+        let named = |full_name: &str| Repo {
+            full_name: full_name.to_string(),
+            ..repo(Some("2024-01-01T00:00:00Z"))
+        };
+        let repos = [
+            named("AndreasOM/omt"),
+            Repo { pushed_at: Some("2015-01-01T00:00:00Z".to_string()), ..named("AndreasOM/fiiish-v3") },
+            named("NewOrg/Shader-Crusher"),
+            Repo { is_archived: true, ..named("AndreasOM/old-game") },
+        ];
+        let stats = collect_user_repo_stats(&repos, now(), Weighting::Bytes).unwrap();
+        assert_eq!(stats.archived_repos, ["AndreasOM/old-game"]);
+        assert_eq!(stats.dormant_repos.len(), 1);
+
+        let entry = |full_name, category, blurb| FeaturedConfig { full_name, category, blurb };
+        let config = [
+            entry("AndreasOM/fiiish-v3", "Games", "A fish"),
+            entry("AndreasOM/omt", "Tools", "Tools"),
+            entry("AndreasOM/old-game", "Games", "Archived"),
+            entry("AndreasOM/shader-crusher", "Tools", "Renamed"),
+            entry("AndreasOM/deleted", "Games", "Missing"),
+            entry("AndreasOM/omt", "", "Uncategorized"),
+        ];
+        let featured: Vec<_> = featured_repos(&config, &stats)
+            .iter()
+            .map(|c| (c.name, c.repos.iter().map(|f| (f.repo.full_name.as_str(), f.blurb)).collect::<Vec<_>>()))
+            .collect();
+        assert_eq!(
+            featured,
+            [
+                ("Games", vec![("AndreasOM/fiiish-v3", "A fish")]),
+                ("Tools", vec![("AndreasOM/omt", "Tools"), ("NewOrg/Shader-Crusher", "Renamed")]),
+                ("", vec![("AndreasOM/omt", "Uncategorized")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn whole_years_between_dates() {
+        let date = |s| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        assert_eq!(whole_years(date("2020-06-15"), date("2024-06-15")), 4);
+        assert_eq!(whole_years(date("2020-06-16"), date("2024-06-15")), 3);
+        assert_eq!(whole_years(date("2020-02-29"), date("2021-02-28")), 0);
+        assert_eq!(whole_years(date("2024-06-01"), date("2024-06-15")), 0);
+    }
+
+    #[test]
+    fn cutoff_is_two_calendar_years_back() {
+        let cutoff = |now: &str| {
+            recent_cutoff(DateTime::parse_from_rfc3339(now).unwrap().with_timezone(&Utc)).to_rfc3339()
+        };
+        assert_eq!(cutoff("2024-06-15T12:00:00Z"), "2022-06-15T12:00:00+00:00");
+        assert_eq!(cutoff("2024-02-29T08:30:00Z"), "2022-02-28T08:30:00+00:00");
+        assert_eq!(cutoff("2024-03-01T00:00:00Z"), "2022-03-01T00:00:00+00:00");
+    }
+
+    fn total(color: &str, bytes: i64) -> LanguageTotal {
+        LanguageTotal {
+            color: color.to_string(),
+            bytes,
+            weight: bytes as f64,
+        }
+    }
+
+    fn languages(entries: &[(&str, &str, i64)]) -> HashMap<String, LanguageTotal> {
+        entries
+            .iter()
+            .map(|(name, color, bytes)| (name.to_string(), total(color, *bytes)))
+            .collect()
+    }
+
+    #[test]
+    fn groups_languages() {
+        let groups = [
+            LanguageGroup { name: "Build/Scripts", color: Some("#89e051"), members: &["Shell", "Makefile"] },
+            LanguageGroup { name: "Web", color: None, members: &["HTML", "CSS", "SCSS"] },
+        ];
+        let raw = languages(&[
+            ("Rust", "#dea584", 500),
+            ("Shell", "#89e051", 30),
+            ("Makefile", "#427819", 20),
+            ("HTML", "#e34c26", 100),
+            ("CSS", "#563d7c", 150),
+            ("SCSS", "#c6538c", 150),
+        ]);
+        let grouped = group_languages(&raw, &groups);
+        assert_eq!(
+            grouped,
+            languages(&[
+                ("Rust", "#dea584", 500),
+                ("Build/Scripts", "#89e051", 50),
+                // CSS and SCSS tie, and CSS comes first.
+                ("Web", "#563d7c", 400),
+            ])
+        );
+
+        let top = top_languages(&grouped, LanguageCutoff::MinPercentage(1.0));
+        let names: Vec<_> = top.iter().map(|l| (l.name, l.percentage)).collect();
+        assert_eq!(names, [("Rust", 53), ("Web", 42), ("Build/Scripts", 5)]);
+    }
+
+    #[test]
+    fn language_groups_are_counted() {
+        for group in LANGUAGE_GROUPS {
+            let counted = group.members.iter().any(|member| {
+                linguist::lookup(member).is_none_or(|l| COUNTED_LANGUAGE_TYPES.contains(&l.kind))
+            });
+            assert!(counted, "none of the {} languages are counted", group.name);
+        }
+    }
+
+    #[test]
+    fn aliases_are_merged() {
+        let mut stats = HashMap::new();
+        let language = |name: &str, bytes| Language { name: name.to_string(), color: None, bytes };
+        collect_language_stats(&mut stats, "AndreasOM/old", &[language("Perl 6", 10)], |_| 1.0);
+        collect_language_stats(&mut stats, "AndreasOM/new", &[language("Raku", 5)], |_| 1.0);
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats["Raku"].bytes, 15);
+    }
+
+    #[test]
+    fn only_counted_types_are_collected() {
+        let mut stats = HashMap::new();
+        let language = |name: &str, bytes| Language { name: name.to_string(), color: None, bytes };
+        let languages = [
+            language("Rust", 100),
+            language("HTML", 50),
+            language("JSON", 20),
+            language("Markdown", 10),
+            language("Klingon", 5),
+        ];
+        collect_language_stats(&mut stats, "AndreasOM/repo", &languages, |_| 1.0);
+        let mut names: Vec<_> = stats.iter().map(|(name, l)| (name.as_str(), l.color.as_str())).collect();
+        names.sort();
+        assert_eq!(names, [("Klingon", "#808080"), ("Rust", "#dea584")]);
+    }
+
+    #[test]
+    fn weighted_and_raw_percentages() {
+        let language = |name: &str, bytes| Language { name: name.to_string(), color: None, bytes };
+        let repos = [
+            Repo {
+                full_name: "AndreasOM/huge".to_string(),
+                languages: vec![language("C++", 900)],
+                ..repo(Some("2024-01-01T00:00:00Z"))
+            },
+            Repo {
+                full_name: "AndreasOM/small".to_string(),
+                languages: vec![language("Rust", 75), language("Python", 25)],
+                ..repo(Some("2024-01-01T00:00:00Z"))
+            },
+        ];
+        let stats = collect_user_repo_stats(&repos, now(), Weighting::EqualPerRepo).unwrap();
+        assert_eq!(stats.language_weighting, "equal per repo");
+        assert!(stats.languages_weighted);
+        assert_eq!(stats.all_time_languages["Python"].bytes, 25);
+        let top: Vec<_> = top_languages(&stats.all_time_languages, LanguageCutoff::Top(3))
+            .iter()
+            .map(|l| (l.name, l.percentage, l.raw_percentage))
+            .collect();
+        assert_eq!(top, [("C++", 50, 90), ("Rust", 38, 8), ("Python", 12, 2)]);
+
+        // Counting only by bytes, the two views agree.
+        let stats = collect_user_repo_stats(&repos, now(), Weighting::Bytes).unwrap();
+        assert!(!stats.languages_weighted);
+        assert!(top_languages(&stats.all_time_languages, LanguageCutoff::Top(3)).iter().all(|l| l.percentage == l.raw_percentage));
+    }
+
+    #[test]
+    fn small_languages_go_to_other() {
+        let raw = languages(&[
+            ("Rust", "#dea584", 6000),
+            ("C++", "#f34b7d", 3000),
+            ("Lua", "#000080", 900),
+            ("Zig", "#ec915c", 60),
+            ("Awk", "#c30e9b", 40),
+        ]);
+        let summary = |cutoff| {
+            top_languages(&raw, cutoff)
+                .into_iter()
+                .map(|l| (l.name, l.percentage, l.members))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            summary(LanguageCutoff::MinPercentage(1.0)),
+            [
+                ("Rust", 60, vec![]),
+                ("C++", 30, vec![]),
+                ("Lua", 9, vec![]),
+                ("Other", 1, vec!["Zig", "Awk"]),
+            ]
+        );
+        assert_eq!(
+            summary(LanguageCutoff::Top(1)),
+            [
+                ("Rust", 60, vec![]),
+                ("Other", 40, vec!["C++", "Lua", "Zig", "Awk"]),
+            ]
+        );
+        assert_eq!(summary(LanguageCutoff::Top(5)).len(), 5);
+        let other = top_languages(&raw, LanguageCutoff::Top(3)).pop().unwrap();
+        assert_eq!((other.name, other.bytes), ("Other", human_bytes(100.0)));
+        assert!(top_languages(&HashMap::new(), LanguageCutoff::Top(5)).is_empty());
+        // Zig and Awk are only 0.4% together when there's more Rust.
+        let raw = languages(&[("Rust", "#dea584", 24_000), ("C++", "#f34b7d", 3000), ("Zig", "#ec915c", 60), ("Awk", "#c30e9b", 40)]);
+        let names: Vec<_> = top_languages(&raw, LanguageCutoff::MinPercentage(1.0)).iter().map(|l| l.name).collect();
+        assert_eq!(names, ["Rust", "C++"]);
+        // Heavily down-weighted languages are 0% weighted, but not raw.
+        let mut raw = languages(&[("Rust", "#dea584", 100), ("C++", "#f34b7d", 5000), ("Lua", "#000080", 3000)]);
+        raw.get_mut("Rust").unwrap().weight = 10_000.0;
+        raw.get_mut("C++").unwrap().weight = 20.0;
+        raw.get_mut("Lua").unwrap().weight = 12.0;
+        let summary: Vec<_> = top_languages(&raw, LanguageCutoff::MinPercentage(1.0))
+            .into_iter()
+            .map(|l| (l.name, l.percentage, l.raw_percentage, l.members))
+            .collect();
+        assert_eq!(summary, [("Rust", 100, 1, vec![]), ("Other", 0, 99, vec!["C++", "Lua"])]);
+    }
+
+    #[test]
+    fn rounded_percentages_add_up() {
+        assert_eq!(largest_remainder(&[33.3, 33.3, 33.4]), [33, 33, 34]);
+        assert_eq!(largest_remainder(&[100.0 / 3.0; 3]), [34, 33, 33]);
+        assert_eq!(largest_remainder(&[52.6, 42.1, 5.3]), [53, 42, 5]);
+        assert_eq!(largest_remainder(&[0.4, 0.4, 99.2]), [1, 0, 99]);
+        assert_eq!(largest_remainder(&[0.0, 0.0]), [0, 0]);
+        assert_eq!(largest_remainder(&[]), Vec::<i64>::new());
+    }
+
+    #[test]
+    fn push_activity_by_month() {
+        let repo = |pushed_date: &str| MyRepo {
+            full_name: String::new(),
+            url: String::new(),
+            fork_count: 0,
+            stargazer_count: 0,
+            pushed_date: pushed_date.to_string(),
+            created_date: String::new(),
+            age_years: 0,
+            description: None,
+            homepage_url: None,
+            primary_language: None,
+            open_graph_image_url: None,
+        };
+        let repos = [repo("2024-06-01"), repo("2024-06-14"), repo("2023-07-31"), repo("2023-06-30")];
+        let months = push_activity(&repos, now());
+        assert_eq!(months.len(), 12);
+        assert_eq!(months[0], MonthlyPushes { month: "2023-07".to_string(), repos: 1 });
+        assert_eq!(months[5], MonthlyPushes { month: "2023-12".to_string(), repos: 0 });
+        assert_eq!(months[6].month, "2024-01");
+        assert_eq!(months[11], MonthlyPushes { month: "2024-06".to_string(), repos: 2 });
+    }
+
+    #[test]
+    fn star_growth_over_time() {
+        let repo = |name: &str, days: &[&str]| RepoStars {
+            full_name: name.to_string(),
+            url: String::new(),
+            starred_on: days.iter().map(|d| d.to_string()).collect(),
+        };
+        let history = StarHistory {
+            repos: vec![
+                repo("a/old", &["2020-01-01", "2020-01-01", "2023-12-01"]),
+                repo("a/new", &["2024-06-01", "2024-06-10"]),
+                repo("a/none", &[]),
+            ],
+        };
+        let growth = star_growth(&history, now());
+        let count = |date: &str, stars| StarCount { date: date.to_string(), stars };
+        assert_eq!(
+            growth.total,
+            [count("2020-01-01", 2), count("2023-12-01", 3), count("2024-06-01", 4), count("2024-06-10", 5)]
+        );
+        assert_eq!((growth.gained_last_30_days, growth.gained_last_365_days), (2, 3));
+        let names = |repos: &[RepoStarGrowth<'_>]| repos.iter().map(|r| r.full_name.to_string()).collect::<Vec<_>>();
+        assert_eq!(names(&growth.repos), ["a/old", "a/new"]);
+        assert_eq!(growth.repos[0].series, [count("2020-01-01", 2), count("2023-12-01", 3)]);
+        assert_eq!(names(&growth.fastest_growing), ["a/new", "a/old"]);
+        assert_eq!(growth.fastest_growing[1].gained_last_30_days, 0);
+    }
+}
@@ -0,0 +1,129 @@
+// Compares a freshly rendered README with the one on disk, for CI to detect
+// drift without rewriting anything.
+use anyhow::Result;
+use regex::Regex;
+use similar::TextDiff;
+use std::{fs, io::ErrorKind, path::Path};
+
+/// Matches what changes in the README just because time passes: dates like
+/// the push dates, relative dates, ages, and the stars gained in a window
+/// ending today.
+pub const DATE_PATTERNS: &[&str] = &[
+    r"\d{4}-\d{2}-\d{2}",
+    r"\b(?:today|yesterday|a year ago|\d+ (?:days|weeks|months|years) ago)\b",
+    r"\b\d+ years? (?:old|of coding in public)\b",
+    r"\b\d+ new(?: stars?)? in the last 30 days, \d+ in the last year\b",
+];
+
+/// [`DATE_PATTERNS`], compiled.
+pub fn date_patterns() -> Vec<Regex> {
+    DATE_PATTERNS
+        .iter()
+        .map(|p| Regex::new(p).expect("DATE_PATTERNS are valid"))
+        .collect()
+}
+
+/// Returns a unified diff between the file at `path` and `rendered`, or `None`
+/// when they match. Text matching any of `ignore` is masked on both sides
+/// before comparing. A missing file counts as empty.
+pub fn drift(path: &Path, rendered: &str, ignore: &[Regex]) -> Result<Option<String>> {
+    let on_disk = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+    let name = path.display().to_string();
+    Ok(diff(&on_disk, rendered, ignore, &name))
+}
+
+fn diff(on_disk: &str, rendered: &str, ignore: &[Regex], name: &str) -> Option<String> {
+    let on_disk = mask(on_disk, ignore);
+    let rendered = mask(rendered, ignore);
+    if on_disk == rendered {
+        return None;
+    }
+    Some(
+        TextDiff::from_lines(&on_disk, &rendered)
+            .unified_diff()
+            .context_radius(3)
+            .header(name, &format!("{name} (generated)"))
+            .to_string(),
+    )
+}
+
+fn mask(text: &str, ignore: &[Regex]) -> String {
+    ignore.iter().fold(text.to_string(), |text, re| re.replace_all(&text, "<ignored>").into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{aggregate, render_readme, sample, weighting::Weighting, Context, LanguageGroupTotals, Sources};
+    use chrono::Duration;
+
+    const README: &str = "# Me\n\n- [a/b](https://x/a/b) on 2024-05-01\n- [a/c](https://x/a/c) on 2024-04-01\n";
+
+    #[test]
+    fn identical_content_has_no_drift() {
+        assert_eq!(diff(README, README, &[], "README.md"), None);
+    }
+
+    #[test]
+    fn drift_is_a_unified_diff() {
+        let rendered = README.replace("a/c", "a/d");
+        let diff = diff(README, &rendered, &[], "README.md").unwrap();
+        assert!(diff.starts_with("--- README.md\n+++ README.md (generated)\n"), "{diff}");
+        assert!(diff.contains("\n-- [a/c](https://x/a/c) on 2024-04-01\n"), "{diff}");
+        assert!(diff.contains("\n+- [a/d](https://x/a/d) on 2024-04-01\n"), "{diff}");
+    }
+
+    #[test]
+    fn ignored_patterns_are_masked() {
+        let rendered = README.replace("2024-05-01", "2024-06-02");
+        assert!(diff(README, &rendered, &[], "README.md").is_some());
+        assert_eq!(diff(README, &rendered, &date_patterns(), "README.md"), None);
+    }
+
+    #[test]
+    fn star_gains_are_masked() {
+        let masked = |text: &str| mask(text, &date_patterns());
+        assert_eq!(
+            masked("- 17 new stars in the last 30 days, 233 in the last year"),
+            masked("- 1 new star in the last 30 days, 240 in the last year")
+        );
+        assert_eq!(
+            masked("- [a/b](https://x/a/b) - 3 new in the last 30 days, 9 in the last year"),
+            masked("- [a/b](https://x/a/b) - 0 new in the last 30 days, 8 in the last year")
+        );
+        assert_ne!(masked("- 17 stars"), masked("- 18 stars"));
+    }
+
+    #[test]
+    fn the_readme_doesnt_drift_as_time_passes() {
+        let render = |now| {
+            // Collected anew each time, like on every run.
+            let mut stats = aggregate::collect_user_repo_stats(&sample::repos(), now, Weighting::Bytes).unwrap();
+            stats.created_at = sample::user_and_repo_stats().created_at;
+            let groups = LanguageGroupTotals::new(&stats);
+            let activity = sample::issue_and_pr_stats();
+            let context = Context::new(
+                Some(&stats),
+                Some(&groups),
+                sample::featured(),
+                Some(&activity),
+                // The fastest growing repos are reordered as the window moves,
+                // which is real drift.
+                None,
+                Sources::default(),
+                now,
+            );
+            render_readme(&context).unwrap()
+        };
+        let today = render(sample::now());
+        // Still in the same month, so the monthly push activity is the same.
+        let later = render(sample::now() + Duration::days(14));
+        assert!(diff(&today, &later, &[], "README.md").is_some());
+        let drift = diff(&today, &later, &date_patterns(), "README.md");
+        assert_eq!(drift, None, "{}", drift.as_deref().unwrap_or_default());
+    }
+}
@@ -0,0 +1,222 @@
+// Commits (and optionally pushes) the regenerated output, so the workflow
+// doesn't have to script git itself.
+use anyhow::Result;
+use serde::Serialize;
+use std::{fmt, path::Path, process::Command};
+use tinytemplate::TinyTemplate;
+
+use crate::UserAndRepoStats;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Author {
+    pub name: String,
+    pub email: String,
+}
+
+impl std::str::FromStr for Author {
+    type Err = anyhow::Error;
+
+    /// Parses `Name <email>`.
+    fn from_str(s: &str) -> Result<Self> {
+        let (name, rest) = s
+            .split_once('<')
+            .ok_or_else(|| anyhow::anyhow!("Expected `Name <email>`, got `{s}`"))?;
+        let email = rest
+            .strip_suffix('>')
+            .ok_or_else(|| anyhow::anyhow!("Expected `Name <email>`, got `{s}`"))?;
+        Ok(Self {
+            name: name.trim().to_string(),
+            email: email.trim().to_string(),
+        })
+    }
+}
+
+/// What changed in the stats since the last run, for the commit message.
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub struct ChangeSummary {
+    pub stars: i64,
+    pub forks: i64,
+    pub repos: i64,
+}
+
+impl ChangeSummary {
+    pub fn between(old: &UserAndRepoStats, new: &UserAndRepoStats) -> Self {
+        Self {
+            stars: new.total_stars - old.total_stars,
+            forks: new.total_forks - old.total_forks,
+            repos: new.owned_repos - old.owned_repos,
+        }
+    }
+}
+
+/// Formats as e.g. `stars +3, 2 new repos`, leaving out what didn't change.
+impl fmt::Display for ChangeSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = |n: i64| if n.abs() == 1 { "" } else { "s" };
+        let mut parts = vec![];
+        if self.stars != 0 {
+            parts.push(format!("stars {:+}", self.stars));
+        }
+        if self.forks != 0 {
+            parts.push(format!("forks {:+}", self.forks));
+        }
+        if self.repos > 0 {
+            parts.push(format!("{} new repo{}", self.repos, plural(self.repos)));
+        } else if self.repos < 0 {
+            parts.push(format!("{} repo{} gone", -self.repos, plural(self.repos)));
+        }
+        write!(f, "{}", parts.join(", "))
+    }
+}
+
+#[derive(Serialize)]
+struct MessageContext<'a> {
+    // Empty when nothing changed or there's nothing to compare against.
+    summary: String,
+    changes: Option<&'a ChangeSummary>,
+}
+
+/// Renders the commit message `template`, which can use `{summary}` and the
+/// individual `{changes.stars}`, `{changes.forks}` and `{changes.repos}`.
+pub fn commit_message(template: &str, changes: Option<&ChangeSummary>) -> Result<String> {
+    let mut tt = TinyTemplate::new();
+    tt.set_default_formatter(&tinytemplate::format_unescaped);
+    tt.add_template("message", template)?;
+    let context = MessageContext {
+        summary: changes.map(|c| c.to_string()).unwrap_or_default(),
+        changes,
+    };
+    Ok(tt.render("message", &context)?)
+}
+
+/// Commits `files` in the repo at `repo_dir` if any of them changed, and
+/// pushes the commit when `push` is set. Returns whether a commit was made.
+pub fn commit_if_changed(
+    repo_dir: &Path,
+    files: &[&Path],
+    author: &Author,
+    message: &str,
+    push: bool,
+) -> Result<bool> {
+    let status = git(repo_dir, &[&["status", "--porcelain", "--"], &paths(files)[..]].concat())?;
+    if status.trim().is_empty() {
+        tracing::info!("Nothing changed; not committing");
+        return Ok(false);
+    }
+
+    git(repo_dir, &[&["add", "--"], &paths(files)[..]].concat())?;
+    let user_name = format!("user.name={}", author.name);
+    let user_email = format!("user.email={}", author.email);
+    git(
+        repo_dir,
+        &[
+            &["-c", &user_name, "-c", &user_email, "commit", "--quiet", "-m", message, "--"],
+            &paths(files)[..],
+        ]
+        .concat(),
+    )?;
+    tracing::info!("Committed: {}", message.lines().next().unwrap_or_default());
+
+    if push {
+        git(repo_dir, &["push", "--quiet"])?;
+        tracing::info!("Pushed");
+    }
+    Ok(true)
+}
+
+fn paths<'a>(files: &[&'a Path]) -> Vec<&'a str> {
+    files.iter().filter_map(|f| f.to_str()).collect()
+}
+
+fn git(repo_dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git").arg("-C").arg(repo_dir).args(args).output()?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "git {} failed in {}: {}",
+            args.join(" "),
+            repo_dir.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn run_git(dir: &Path, args: &[&str]) -> String {
+        git(dir, args).unwrap()
+    }
+
+    #[test]
+    fn summary_lists_only_changes() {
+        let old = UserAndRepoStats {
+            total_stars: 10,
+            total_forks: 2,
+            owned_repos: 5,
+            ..Default::default()
+        };
+        let new = UserAndRepoStats {
+            total_stars: 13,
+            total_forks: 2,
+            owned_repos: 7,
+            ..Default::default()
+        };
+        assert_eq!(ChangeSummary::between(&old, &new).to_string(), "stars +3, 2 new repos");
+        assert_eq!(ChangeSummary::between(&new, &old).to_string(), "stars -3, 2 repos gone");
+        assert_eq!(ChangeSummary::between(&old, &old).to_string(), "");
+    }
+
+    #[test]
+    fn message_template() {
+        let template = "Regenerated profile README.md{{ if summary }} ({summary}){{ endif }}";
+        let changes = ChangeSummary { stars: 1, forks: 0, repos: 1 };
+        assert_eq!(
+            commit_message(template, Some(&changes)).unwrap(),
+            "Regenerated profile README.md (stars +1, 1 new repo)"
+        );
+        assert_eq!(commit_message(template, None).unwrap(), "Regenerated profile README.md");
+    }
+
+    #[test]
+    fn author_parsing() {
+        let author: Author = "Murderbot <murderbot@example.com>".parse().unwrap();
+        assert_eq!(author.name, "Murderbot");
+        assert_eq!(author.email, "murderbot@example.com");
+        assert!("Murderbot".parse::<Author>().is_err());
+    }
+
+    #[test]
+    fn commits_and_pushes_to_bare_repo() {
+        let tmp = tempfile::tempdir().unwrap();
+        let bare = tmp.path().join("origin.git");
+        let work = tmp.path().join("work");
+        run_git(tmp.path(), &["init", "--quiet", "--bare", "origin.git"]);
+        run_git(tmp.path(), &["clone", "--quiet", bare.to_str().unwrap(), "work"]);
+        let author = Author {
+            name: "Murderbot".to_string(),
+            email: "murderbot@example.com".to_string(),
+        };
+        let readme = Path::new("README.md");
+
+        fs::write(work.join(readme), "# Hi\n").unwrap();
+        assert!(commit_if_changed(&work, &[readme], &author, "Regenerated (stars +1)", true).unwrap());
+
+        let log = run_git(&bare, &["log", "--format=%an <%ae> %s"]);
+        assert_eq!(log, "Murderbot <murderbot@example.com> Regenerated (stars +1)\n");
+
+        // Same content again is a no-op.
+        fs::write(work.join(readme), "# Hi\n").unwrap();
+        assert!(!commit_if_changed(&work, &[readme], &author, "Regenerated", true).unwrap());
+        assert_eq!(run_git(&bare, &["rev-list", "--count", "HEAD"]), "1\n");
+
+        // Files that weren't asked for are left alone.
+        fs::write(work.join("other.txt"), "other\n").unwrap();
+        fs::write(work.join(readme), "# Hello\n").unwrap();
+        assert!(commit_if_changed(&work, &[readme], &author, "Regenerated again", false).unwrap());
+        assert_eq!(run_git(&work, &["status", "--porcelain"]), "?? other.txt\n");
+        assert_eq!(run_git(&bare, &["rev-list", "--count", "HEAD"]), "1\n");
+    }
+}
@@ -0,0 +1,251 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use super::{header_i64, rest_get_with_retry, Forge, Language, Repo, User};
+use crate::IssueAndPrStats;
+
+const PAGE_LIMIT: usize = 50;
+
+#[derive(Debug, Deserialize)]
+struct GiteaUser {
+    created: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GiteaRepo {
+    full_name: String,
+    html_url: String,
+    description: Option<String>,
+    website: Option<String>,
+    language: Option<String>,
+    stars_count: i64,
+    forks_count: i64,
+    created_at: Option<String>,
+    updated_at: Option<String>,
+    #[serde(default)]
+    archived: bool,
+    #[serde(default)]
+    empty: bool,
+    #[serde(default)]
+    fork: bool,
+    #[serde(default)]
+    mirror: bool,
+    #[serde(default)]
+    private: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct GiteaIssue {
+    pull_request: Option<GiteaPullRequestMeta>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GiteaPullRequestMeta {
+    #[serde(default)]
+    merged: bool,
+}
+
+/// Gitea and Forgejo REST (v1) provider.
+///
+/// The issue search only filters by the authenticated user, so issue and PR
+/// counts need a token belonging to the configured login.
+pub struct GiteaForge {
+    client: Client,
+    base_url: String,
+    login: String,
+}
+
+impl GiteaForge {
+    pub fn new(base_url: &str, login: &str, token: Option<&str>, user_agent: &str) -> Result<Self> {
+        let mut builder = Client::builder().user_agent(user_agent);
+        if let Some(token) = token {
+            builder = builder.default_headers(
+                std::iter::once((
+                    reqwest::header::AUTHORIZATION,
+                    reqwest::header::HeaderValue::from_str(&format!("token {}", token))
+                        .map_err(|e| anyhow::anyhow!("Invalid authorization header: {}", e))?,
+                ))
+                .collect(),
+            );
+        }
+        Ok(Self {
+            client: builder.build()?,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            login: login.to_string(),
+        })
+    }
+
+    async fn languages(&self, full_name: &str) -> Result<Vec<Language>> {
+        let url = format!("{}/api/v1/repos/{}/languages", self.base_url, full_name);
+        let (_, body) = rest_get_with_retry(&self.client, &url).await?;
+        let sizes: HashMap<String, i64> = serde_json::from_str(&body)?;
+        Ok(sizes
+            .into_iter()
+            .map(|(name, bytes)| Language {
+                name,
+                color: None,
+                bytes,
+            })
+            .collect())
+    }
+
+    async fn issue_count(&self, kind: &str, state: &str) -> Result<i64> {
+        let url = format!(
+            "{}/api/v1/repos/issues/search?type={}&state={}&created=true&limit=1",
+            self.base_url, kind, state
+        );
+        let (headers, _) = rest_get_with_retry(&self.client, &url).await?;
+        header_i64(&headers, "x-total-count")
+            .ok_or_else(|| anyhow::anyhow!("No X-Total-Count header in response from {}", url))
+    }
+
+    async fn merged_pr_count(&self) -> Result<i64> {
+        // There is no filter for merged PRs, so we page through the closed ones.
+        let mut merged = 0;
+        let mut page = 1;
+        loop {
+            let url = format!(
+                "{}/api/v1/repos/issues/search?type=pulls&state=closed&created=true&limit={}&page={}",
+                self.base_url, PAGE_LIMIT, page
+            );
+            let (_, body) = rest_get_with_retry(&self.client, &url).await?;
+            let prs: Vec<GiteaIssue> = serde_json::from_str(&body)?;
+            merged += prs
+                .iter()
+                .filter(|pr| pr.pull_request.as_ref().is_some_and(|p| p.merged))
+                .count() as i64;
+            if prs.len() < PAGE_LIMIT {
+                break;
+            }
+            page += 1;
+        }
+        Ok(merged)
+    }
+}
+
+#[async_trait]
+impl Forge for GiteaForge {
+    fn name(&self) -> &str {
+        "Gitea"
+    }
+
+    async fn user_repos(&self) -> Result<User> {
+        tracing::info!("Getting Gitea repos for login {} from {}", self.login, self.base_url);
+        let url = format!("{}/api/v1/users/{}", self.base_url, self.login);
+        let (_, body) = rest_get_with_retry(&self.client, &url).await?;
+        let gitea_user: GiteaUser = serde_json::from_str(&body)?;
+        let mut user = User {
+            created_at: gitea_user.created,
+            repos: vec![],
+        };
+
+        let mut page = 1;
+        loop {
+            let url = format!(
+                "{}/api/v1/users/{}/repos?limit={}&page={}",
+                self.base_url, self.login, PAGE_LIMIT, page
+            );
+            let (_, body) = rest_get_with_retry(&self.client, &url).await?;
+            let repos: Vec<GiteaRepo> = serde_json::from_str(&body)?;
+            let count = repos.len();
+            for repo in repos {
+                let languages = self.languages(&repo.full_name).await?;
+                user.repos.push(Repo {
+                    full_name: repo.full_name,
+                    url: repo.html_url,
+                    stargazer_count: repo.stars_count,
+                    fork_count: repo.forks_count,
+                    created_at: repo.created_at,
+                    // Gitea doesn't expose the last push, but updated_at is
+                    // bumped by pushes.
+                    pushed_at: repo.updated_at,
+                    recent_commits: vec![],
+                    last_commit_at: None,
+                    // Gitea has empty strings rather than none.
+                    description: repo.description.filter(|d| !d.is_empty()),
+                    homepage_url: repo.website.filter(|w| !w.is_empty()),
+                    primary_language: repo.language.filter(|l| !l.is_empty()),
+                    open_graph_image_url: None,
+                    languages,
+                    is_archived: repo.archived,
+                    is_disabled: false,
+                    is_empty: repo.empty,
+                    is_fork: repo.fork,
+                    is_mirror: repo.mirror,
+                    is_private: repo.private,
+                });
+            }
+            if count < PAGE_LIMIT {
+                break;
+            }
+            page += 1;
+        }
+        Ok(user)
+    }
+
+    async fn issue_and_pr_stats(&self) -> Result<IssueAndPrStats> {
+        tracing::info!("Getting Gitea issue and pr data for {}", self.login);
+        Ok(IssueAndPrStats {
+            issues_created: self.issue_count("issues", "all").await?,
+            issues_closed: self.issue_count("issues", "closed").await?,
+            prs_created: self.issue_count("pulls", "all").await?,
+            prs_merged: self.merged_pr_count().await?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forge::stub::StubServer;
+
+    #[tokio::test]
+    async fn user_repos_reads_repos_and_languages() {
+        let repos = r#"[{"full_name":"anti/omt","html_url":"https://codeberg.org/anti/omt","stars_count":2,
+            "forks_count":0,"created_at":"2021-01-01T00:00:00+01:00","updated_at":"2024-05-01T00:00:00+02:00",
+            "archived":false,"empty":false,"fork":false,"mirror":true,"private":false}]"#;
+        let server = StubServer::start(vec![
+            ("/api/v1/users/anti/repos", vec![], repos.to_string()),
+            ("/api/v1/users/anti", vec![], r#"{"created":"2019-01-01T00:00:00Z"}"#.to_string()),
+            ("/api/v1/repos/anti/omt/languages", vec![], r#"{"Rust":1234}"#.to_string()),
+        ]);
+        let forge = GiteaForge::new(&server.url(""), "anti", None, "test").unwrap();
+
+        let user = forge.user_repos().await.unwrap();
+
+        assert_eq!(user.created_at.as_deref(), Some("2019-01-01T00:00:00Z"));
+        assert_eq!(user.repos.len(), 1);
+        let repo = &user.repos[0];
+        assert!(repo.is_mirror);
+        assert_eq!(repo.pushed_at.as_deref(), Some("2024-05-01T00:00:00+02:00"));
+        assert_eq!(repo.languages[0].name, "Rust");
+        assert_eq!(repo.languages[0].bytes, 1234);
+    }
+
+    #[tokio::test]
+    async fn issue_and_pr_stats_counts_merged_prs() {
+        let total = |n: &str| vec![("X-Total-Count", n.to_string())];
+        let server = StubServer::start(vec![
+            ("/api/v1/repos/issues/search?type=issues&state=all", total("6"), "[]".to_string()),
+            ("/api/v1/repos/issues/search?type=issues&state=closed", total("5"), "[]".to_string()),
+            ("/api/v1/repos/issues/search?type=pulls&state=all", total("4"), "[]".to_string()),
+            (
+                "/api/v1/repos/issues/search?type=pulls&state=closed",
+                vec![],
+                r#"[{"pull_request":{"merged":true}},{"pull_request":{"merged":false}}]"#.to_string(),
+            ),
+        ]);
+        let forge = GiteaForge::new(&server.url(""), "anti", Some("token"), "test").unwrap();
+
+        let stats = forge.issue_and_pr_stats().await.unwrap();
+
+        assert_eq!(stats.issues_created, 6);
+        assert_eq!(stats.issues_closed, 5);
+        assert_eq!(stats.prs_created, 4);
+        assert_eq!(stats.prs_merged, 1);
+        assert!(server.requests().iter().all(|r| r.contains("created=true")));
+    }
+}
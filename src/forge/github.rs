@@ -0,0 +1,508 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::Client;
+
+use super::{acquire_request_permit, Commit, Forge, Language, Repo, User};
+use crate::github_queries::{
+    issues_and_prs_query, stargazers_query, starred_repos_query, user_repos_query, IssuesAndPrsQuery,
+    StargazersQuery, StarredReposQuery, UserReposQuery,
+};
+use crate::{IssueAndPrStats, RepoStars};
+
+pub const API_URL: &str = "https://api.github.com/graphql";
+
+// API rate limiting configuration
+const PAGINATION_DELAY_MS: u64 = 200;
+
+pub struct GitHubForge {
+    client: Client,
+    api_url: String,
+    login: String,
+}
+
+impl GitHubForge {
+    pub fn new(api_url: &str, login: &str, token: &str, user_agent: &str) -> Result<Self> {
+        let client = Client::builder()
+            .user_agent(user_agent)
+            .default_headers(
+                std::iter::once((
+                    reqwest::header::AUTHORIZATION,
+                    reqwest::header::HeaderValue::from_str(&format!("Bearer {}", token))
+                        .map_err(|e| anyhow::anyhow!("Invalid authorization header: {}", e))?,
+                ))
+                .collect(),
+            )
+            .build()?;
+        Ok(Self {
+            client,
+            api_url: api_url.to_string(),
+            login: login.to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl Forge for GitHubForge {
+    fn name(&self) -> &str {
+        "GitHub"
+    }
+
+    async fn user_repos(&self) -> Result<User> {
+        let mut user_data = User::default();
+        let mut after = None;
+        tracing::info!("Getting user repos for login: {}", self.login);
+        loop {
+            let vars = user_repos_query::Variables {
+                login: self.login.clone(),
+                after,
+            };
+            tracing::debug!("Making GraphQL request to {} for user {}", self.api_url, self.login);
+            let resp = graphql_with_retry::<UserReposQuery>(&self.client, &self.api_url, vars).await?;
+            tracing::debug!("{resp:#?}");
+
+            if resp.data.is_none() {
+                tracing::error!("No data in GraphQL response. Full response: {:#?}", resp);
+            }
+            let data = resp.data.ok_or_else(|| anyhow::anyhow!("No data in GraphQL response"))?;
+
+            if data.user.is_none() {
+                tracing::error!("No user in GraphQL response");
+            }
+            let user = data.user.ok_or_else(|| anyhow::anyhow!("No user in GraphQL response"))?;
+
+            if user_data.created_at.is_none() {
+                user_data.created_at = Some(user.created_at);
+            }
+
+            let nodes = user.repositories
+                    .nodes
+                    .ok_or_else(|| anyhow::anyhow!("No repository nodes in response"))?;
+
+            user_data.repos.extend(
+                nodes
+                    .into_iter()
+                    .flatten()
+                    .filter(|r| r.owner.login == self.login)
+                    .map(normalize_repo),
+            );
+
+            if user.repositories.page_info.has_next_page {
+                after = user.repositories.page_info.end_cursor;
+                // Small delay between paginated requests to avoid rate limiting
+                tokio::time::sleep(tokio::time::Duration::from_millis(PAGINATION_DELAY_MS)).await;
+            } else {
+                break;
+            }
+        }
+        Ok(user_data)
+    }
+
+    async fn issue_and_pr_stats(&self) -> Result<IssueAndPrStats> {
+        tracing::info!("Getting issue and pr data");
+        let resp = graphql_with_retry::<IssuesAndPrsQuery>(
+            &self.client,
+            &self.api_url,
+            issues_and_prs_query::Variables {},
+        )
+        .await?;
+        tracing::debug!("{resp:#?}");
+
+        if resp.data.is_none() {
+            tracing::error!("No data in issues/PRs GraphQL response. Full response: {:#?}", resp);
+        }
+        let data = resp.data.ok_or_else(|| anyhow::anyhow!("No data in issues/PRs GraphQL response"))?;
+        Ok(IssueAndPrStats {
+            issues_created: data.issues_created.issue_count,
+            issues_closed: data.issues_closed.issue_count,
+            prs_created: data.prs_created.issue_count,
+            prs_merged: data.prs_merged.issue_count,
+        })
+    }
+
+    async fn star_history(&self) -> Result<Vec<RepoStars>> {
+        tracing::info!("Getting star history for login: {}", self.login);
+        let starred = self.starred_repos().await?;
+        futures::future::join_all(starred.iter().map(|(full_name, url)| self.repo_stars(full_name, url)))
+            .await
+            .into_iter()
+            .collect()
+    }
+}
+
+impl GitHubForge {
+    // The names and urls of the owned repos with stars, leaving out the same
+    // repos as the repo stats. Only what that takes is fetched, so the star
+    // history doesn't depend on the much larger repos query.
+    async fn starred_repos(&self) -> Result<Vec<(String, String)>> {
+        let mut starred = vec![];
+        let mut after = None;
+        loop {
+            let vars = starred_repos_query::Variables {
+                login: self.login.clone(),
+                after,
+            };
+            let resp = graphql_with_retry::<StarredReposQuery>(&self.client, &self.api_url, vars).await?;
+            let data = resp.data.ok_or_else(|| anyhow::anyhow!("No data in starred repos GraphQL response"))?;
+            let repositories = data
+                .user
+                .ok_or_else(|| anyhow::anyhow!("No user in starred repos GraphQL response"))?
+                .repositories;
+            starred.extend(
+                repositories
+                    .nodes
+                    .unwrap_or_default()
+                    .into_iter()
+                    .flatten()
+                    .filter(|r| r.owner.login == self.login && r.stargazer_count > 0)
+                    .filter(|r| !(r.is_archived || r.is_disabled || r.is_empty || r.is_private))
+                    .filter(|r| !(r.is_fork || r.is_mirror))
+                    .map(|r| (r.name_with_owner, r.url)),
+            );
+
+            if repositories.page_info.has_next_page {
+                after = repositories.page_info.end_cursor;
+                tokio::time::sleep(tokio::time::Duration::from_millis(PAGINATION_DELAY_MS)).await;
+            } else {
+                break;
+            }
+        }
+        Ok(starred)
+    }
+
+    async fn repo_stars(&self, full_name: &str, url: &str) -> Result<RepoStars> {
+        let (owner, name) = full_name
+            .split_once('/')
+            .ok_or_else(|| anyhow::anyhow!("Not an owner/name repo name: {}", full_name))?;
+        let mut starred_on = vec![];
+        let mut after = None;
+        loop {
+            let vars = stargazers_query::Variables {
+                owner: owner.to_string(),
+                name: name.to_string(),
+                after,
+            };
+            let resp = graphql_with_retry::<StargazersQuery>(&self.client, &self.api_url, vars).await?;
+            let data = resp.data.ok_or_else(|| anyhow::anyhow!("No data in stargazers GraphQL response"))?;
+            let stargazers = data
+                .repository
+                .ok_or_else(|| anyhow::anyhow!("No repository {} in stargazers GraphQL response", full_name))?
+                .stargazers;
+            for edge in stargazers.edges.unwrap_or_default().into_iter().flatten() {
+                let starred_at = chrono::DateTime::parse_from_rfc3339(&edge.starred_at)?;
+                starred_on.push(starred_at.with_timezone(&chrono::Utc).format("%Y-%m-%d").to_string());
+            }
+
+            if stargazers.page_info.has_next_page {
+                after = stargazers.page_info.end_cursor;
+                tokio::time::sleep(tokio::time::Duration::from_millis(PAGINATION_DELAY_MS)).await;
+            } else {
+                break;
+            }
+        }
+        tracing::debug!("Got {} stars of {}", starred_on.len(), full_name);
+        // The API sorts by time, but the days may still shift around with
+        // time zones.
+        starred_on.sort();
+        Ok(RepoStars {
+            full_name: full_name.to_string(),
+            url: url.to_string(),
+            starred_on,
+        })
+    }
+}
+
+fn normalize_repo(repo: user_repos_query::ReposNodes) -> Repo {
+    let languages = match repo.languages {
+        Some(langs) => {
+            let sizes: Vec<_> = langs
+                .edges
+                .unwrap_or_default()
+                .into_iter()
+                .flatten()
+                .map(|edge| edge.size)
+                .collect();
+            let names_and_colors: Vec<_> = langs
+                .nodes
+                .unwrap_or_default()
+                .into_iter()
+                .flatten()
+                .collect();
+            if sizes.len() == names_and_colors.len() {
+                names_and_colors
+                    .into_iter()
+                    .zip(sizes)
+                    .map(|(lang, bytes)| Language {
+                        name: lang.name,
+                        color: lang.color,
+                        bytes,
+                    })
+                    .collect()
+            } else {
+                tracing::warn!(
+                    "language sizes and names differ in length: {} != {} for {}; skipping",
+                    sizes.len(),
+                    names_and_colors.len(),
+                    repo.name_with_owner,
+                );
+                vec![]
+            }
+        }
+        None => vec![],
+    };
+
+    let recent_commits = recent_commits(repo.default_branch_ref);
+
+    Repo {
+        full_name: repo.name_with_owner,
+        url: repo.url,
+        stargazer_count: repo.stargazer_count,
+        fork_count: repo.fork_count,
+        created_at: Some(repo.created_at),
+        pushed_at: repo.pushed_at,
+        recent_commits,
+        last_commit_at: None,
+        description: repo.description,
+        // GitHub has an empty homepage rather than none.
+        homepage_url: repo.homepage_url.filter(|u| !u.is_empty()),
+        primary_language: repo.primary_language.map(|l| l.name),
+        open_graph_image_url: Some(repo.open_graph_image_url),
+        languages,
+        is_archived: repo.is_archived,
+        is_disabled: repo.is_disabled,
+        is_empty: repo.is_empty,
+        is_fork: repo.is_fork,
+        is_mirror: repo.is_mirror,
+        is_private: repo.is_private,
+    }
+}
+
+/// The latest commits on the default branch, newest first.
+fn recent_commits(branch: Option<user_repos_query::ReposNodesDefaultBranchRef>) -> Vec<Commit> {
+    use user_repos_query::ReposNodesDefaultBranchRefTarget as Target;
+    let Some(Target::Commit(commit)) = branch.and_then(|b| b.target) else {
+        return vec![];
+    };
+    commit
+        .history
+        .nodes
+        .unwrap_or_default()
+        .into_iter()
+        .flatten()
+        .map(|c| Commit {
+            committed_at: c.committed_date,
+            author: c
+                .author
+                .map(|a| a.user.map(|u| u.login).into_iter().chain(a.name).chain(a.email).collect())
+                .unwrap_or_default(),
+        })
+        .collect()
+}
+
+async fn graphql_with_retry<Q: graphql_client::GraphQLQuery>(
+    client: &Client,
+    url: &str,
+    variables: Q::Variables,
+) -> Result<graphql_client::Response<Q::ResponseData>>
+where
+    Q::Variables: serde::Serialize + Clone,
+{
+    const MAX_RETRIES: u32 = 4;
+    const INITIAL_DELAY_MS: u64 = 1000;
+
+    for attempt in 1..=MAX_RETRIES {
+        tracing::debug!("GraphQL request attempt {}/{}", attempt, MAX_RETRIES);
+
+        // Build the request body
+        let request_body = graphql_client::QueryBody {
+            variables: variables.clone(),
+            query: Q::build_query(variables.clone()).query,
+            operation_name: Q::build_query(variables.clone()).operation_name,
+        };
+
+        // Make the HTTP request directly to capture the raw response. The
+        // permit is only held for the request itself, not while backing off.
+        let http_response = {
+            let _permit = acquire_request_permit().await?;
+            match client.post(url).json(&request_body).send().await {
+                Ok(response) => Ok((response.status(), response.text().await)),
+                Err(e) => Err(e),
+            }
+        };
+
+        match http_response {
+            Ok((status, response_text)) => {
+                tracing::debug!("HTTP response status: {}", status);
+
+                // Get the raw response text
+                let response_text = match response_text {
+                    Ok(text) => text,
+                    Err(e) => {
+                        tracing::error!("Failed to read response body: {}", e);
+                        if attempt < MAX_RETRIES {
+                            let delay_ms = INITIAL_DELAY_MS * 2u64.pow(attempt - 1);
+                            tracing::warn!("Retrying in {}ms...", delay_ms);
+                            tokio::time::sleep(tokio::time::Duration::from_millis(delay_ms)).await;
+                            continue;
+                        } else {
+                            return Err(anyhow::anyhow!("Failed to read response body: {}", e));
+                        }
+                    }
+                };
+
+                // Try to parse as GraphQL response
+                match serde_json::from_str::<graphql_client::Response<Q::ResponseData>>(&response_text) {
+                    Ok(graphql_response) => {
+                        tracing::debug!("GraphQL request succeeded on attempt {}", attempt);
+                        return Ok(graphql_response);
+                    }
+                    Err(e) => {
+                        tracing::error!("GraphQL request attempt {}/{} failed to parse response", attempt, MAX_RETRIES);
+                        tracing::error!("Parse error: {}", e);
+                        tracing::error!("HTTP status: {}", status);
+                        tracing::error!("Full response body:\n{}", response_text);
+
+                        if attempt < MAX_RETRIES {
+                            let delay_ms = INITIAL_DELAY_MS * 2u64.pow(attempt - 1);
+                            tracing::warn!("Retrying in {}ms...", delay_ms);
+                            tokio::time::sleep(tokio::time::Duration::from_millis(delay_ms)).await;
+                        } else {
+                            tracing::error!("All {} retry attempts exhausted", MAX_RETRIES);
+                            return Err(anyhow::anyhow!(
+                                "GraphQL request failed after {} attempts. Last error: {}. HTTP status: {}",
+                                MAX_RETRIES,
+                                e,
+                                status
+                            ));
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                tracing::error!("HTTP request attempt {}/{} failed: {}", attempt, MAX_RETRIES, e);
+
+                if attempt < MAX_RETRIES {
+                    let delay_ms = INITIAL_DELAY_MS * 2u64.pow(attempt - 1);
+                    tracing::warn!("Retrying in {}ms...", delay_ms);
+                    tokio::time::sleep(tokio::time::Duration::from_millis(delay_ms)).await;
+                } else {
+                    tracing::error!("All {} retry attempts exhausted", MAX_RETRIES);
+                    return Err(anyhow::anyhow!("HTTP request failed after {} attempts: {}", MAX_RETRIES, e));
+                }
+            }
+        }
+    }
+
+    unreachable!("Loop should always return before reaching here");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forge::stub::StubServer;
+
+    const REPOS_RESPONSE: &str = r##"{"data":{"user":{"createdAt":"2010-01-01T00:00:00Z","repositories":{
+        "pageInfo":{"endCursor":null,"hasNextPage":false},
+        "nodes":[
+            {"createdAt":"2020-01-01T00:00:00Z","pushedAt":"2024-05-01T00:00:00Z","forkCount":2,
+             "defaultBranchRef":{"target":{"__typename":"Commit","history":{"nodes":[
+                {"committedDate":"2024-05-01T00:00:00Z","author":{"email":"49699333+dependabot[bot]@users.noreply.github.com",
+                 "name":"dependabot[bot]","user":{"login":"dependabot[bot]"}}},
+                {"committedDate":"2024-04-20T00:00:00Z","author":{"email":"bot@example.com","name":"Renovate Bot","user":null}},
+                {"committedDate":"2023-11-02T00:00:00Z","author":{"email":"andreas@example.com","name":"Andreas",
+                 "user":{"login":"AndreasOM"}}}]}}},
+             "isArchived":false,"isDisabled":false,"isEmpty":false,"isFork":false,"isMirror":false,"isPrivate":false,
+             "nameWithOwner":"AndreasOM/omt","description":"Tools for\nmaking games","homepageUrl":"",
+             "openGraphImageUrl":"https://opengraph.example.com/omt","primaryLanguage":{"color":"#dea584","name":"Rust"},
+             "languages":{"edges":[{"size":1000},{"size":10}],
+             "nodes":[{"color":"#dea584","name":"Rust"},{"color":null,"name":"Shell"}],"totalSize":1010},
+             "licenseInfo":null,"owner":{"__typename":"User","login":"AndreasOM"},"stargazerCount":7,
+             "url":"https://github.com/AndreasOM/omt"},
+            {"createdAt":"2020-01-01T00:00:00Z","pushedAt":null,"forkCount":0,"defaultBranchRef":null,
+             "isArchived":false,"isDisabled":false,"isEmpty":false,"isFork":false,"isMirror":false,"isPrivate":false,
+             "nameWithOwner":"someone/else","description":null,"homepageUrl":null,
+             "openGraphImageUrl":"https://opengraph.example.com/else","primaryLanguage":null,"languages":null,
+             "licenseInfo":null,"owner":{"__typename":"User","login":"someone"},"stargazerCount":0,
+             "url":"https://github.com/someone/else"}
+        ]}}}}"##;
+
+    #[tokio::test]
+    async fn user_repos_are_normalized_and_filtered_by_owner() {
+        let server = StubServer::start(vec![("/graphql", vec![], REPOS_RESPONSE.to_string())]);
+        let forge = GitHubForge::new(&server.url("/graphql"), "AndreasOM", "token", "test").unwrap();
+
+        let user = forge.user_repos().await.unwrap();
+
+        assert_eq!(user.created_at.as_deref(), Some("2010-01-01T00:00:00Z"));
+        assert_eq!(user.repos.len(), 1);
+        let repo = &user.repos[0];
+        assert_eq!(repo.full_name, "AndreasOM/omt");
+        assert_eq!(repo.stargazer_count, 7);
+        assert_eq!(repo.languages.len(), 2);
+        assert_eq!(repo.languages[0].name, "Rust");
+        assert_eq!(repo.languages[0].bytes, 1000);
+        assert_eq!(repo.languages[1].color, None);
+        assert_eq!(repo.description.as_deref(), Some("Tools for\nmaking games"));
+        assert_eq!(repo.homepage_url, None);
+        assert_eq!(repo.primary_language.as_deref(), Some("Rust"));
+        assert_eq!(repo.open_graph_image_url.as_deref(), Some("https://opengraph.example.com/omt"));
+        assert_eq!(repo.pushed_at.as_deref(), Some("2024-05-01T00:00:00Z"));
+        assert_eq!(repo.recent_commits.len(), 3);
+        assert_eq!(repo.recent_commits[0].author, ["dependabot[bot]", "dependabot[bot]", "49699333+dependabot[bot]@users.noreply.github.com"]);
+        assert_eq!(repo.recent_commits[1].author, ["Renovate Bot", "bot@example.com"]);
+        assert_eq!(repo.recent_commits[2].committed_at, "2023-11-02T00:00:00Z");
+    }
+
+    #[tokio::test]
+    async fn star_history_covers_the_listed_repos_with_stars() {
+        let repo = |name: &str, owner: &str, stars: i64, flag: &str| {
+            let flags = ["isArchived", "isDisabled", "isEmpty", "isFork", "isMirror", "isPrivate"]
+                .map(|f| format!(r#""{f}":{}"#, f == flag))
+                .join(",");
+            format!(
+                r#"{{{flags},"nameWithOwner":"{owner}/{name}","owner":{{"login":"{owner}"}},"stargazerCount":{stars},"url":"https://github.com/{owner}/{name}"}}"#
+            )
+        };
+        let nodes = [
+            repo("omt", "AndreasOM", 2, ""),
+            repo("old-engine", "AndreasOM", 5, "isArchived"),
+            repo("gone", "AndreasOM", 1, "isDisabled"),
+            repo("fork", "AndreasOM", 3, "isFork"),
+            repo("unstarred", "AndreasOM", 0, ""),
+            repo("else", "someone", 9, ""),
+        ];
+        let repos = format!(
+            r#"{{"data":{{"user":{{"repositories":{{"pageInfo":{{"endCursor":null,"hasNextPage":false}},"nodes":[{}]}}}}}}}}"#,
+            nodes.join(",")
+        );
+        let stargazers = r#"{"data":{"repository":{"stargazers":{"pageInfo":{"endCursor":null,"hasNextPage":false},
+            "edges":[{"starredAt":"2024-01-02T03:04:05Z"},{"starredAt":"2024-03-04T05:06:07Z"}]}}}}"#;
+        let server = StubServer::start(vec![
+            (r#"/graphql "operationName":"StarredReposQuery""#, vec![], repos),
+            (r#"/graphql "operationName":"StargazersQuery""#, vec![], stargazers.to_string()),
+        ]);
+        let forge = GitHubForge::new(&server.url("/graphql"), "AndreasOM", "token", "test").unwrap();
+
+        let history = forge.star_history().await.unwrap();
+
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].full_name, "AndreasOM/omt");
+        assert_eq!(history[0].starred_on, ["2024-01-02", "2024-03-04"]);
+        // One page of repos, and one of stargazers.
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn issue_and_pr_stats_reads_search_counts() {
+        let body = r#"{"data":{"issues_created":{"issueCount":4},"issues_closed":{"issueCount":3},
+            "prs_created":{"issueCount":2},"prs_merged":{"issueCount":1}}}"#;
+        let server = StubServer::start(vec![("/graphql", vec![], body.to_string())]);
+        let forge = GitHubForge::new(&server.url("/graphql"), "AndreasOM", "token", "test").unwrap();
+
+        let stats = forge.issue_and_pr_stats().await.unwrap();
+
+        assert_eq!(stats.issues_created, 4);
+        assert_eq!(stats.issues_closed, 3);
+        assert_eq!(stats.prs_created, 2);
+        assert_eq!(stats.prs_merged, 1);
+    }
+}
@@ -0,0 +1,254 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+use super::{header_i64, rest_get_with_retry, Forge, Language, Repo, User};
+use crate::IssueAndPrStats;
+
+const PER_PAGE: usize = 100;
+
+#[derive(Debug, Deserialize)]
+struct Project {
+    id: i64,
+    path_with_namespace: String,
+    web_url: String,
+    description: Option<String>,
+    star_count: i64,
+    forks_count: i64,
+    created_at: Option<String>,
+    last_activity_at: Option<String>,
+    #[serde(default)]
+    archived: bool,
+    #[serde(default)]
+    empty_repo: bool,
+    #[serde(default)]
+    mirror: bool,
+    visibility: Option<String>,
+    forked_from_project: Option<serde_json::Value>,
+    statistics: Option<ProjectStatistics>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProjectStatistics {
+    repository_size: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct IssuesStatistics {
+    statistics: IssuesStatisticsInner,
+}
+
+#[derive(Debug, Deserialize)]
+struct IssuesStatisticsInner {
+    counts: IssuesStatisticsCounts,
+}
+
+#[derive(Debug, Deserialize)]
+struct IssuesStatisticsCounts {
+    all: i64,
+    closed: i64,
+}
+
+/// GitLab REST (v4) provider.
+///
+/// GitLab only reports languages as percentages of the repository, so byte
+/// counts are estimated from the repository size in the project statistics.
+/// Those statistics are only visible with a token for a project member.
+pub struct GitLabForge {
+    client: Client,
+    base_url: String,
+    login: String,
+}
+
+impl GitLabForge {
+    pub fn new(base_url: &str, login: &str, token: Option<&str>, user_agent: &str) -> Result<Self> {
+        let mut builder = Client::builder().user_agent(user_agent);
+        if let Some(token) = token {
+            builder = builder.default_headers(
+                std::iter::once((
+                    reqwest::header::HeaderName::from_static("private-token"),
+                    reqwest::header::HeaderValue::from_str(token)
+                        .map_err(|e| anyhow::anyhow!("Invalid private token header: {}", e))?,
+                ))
+                .collect(),
+            );
+        }
+        Ok(Self {
+            client: builder.build()?,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            login: login.to_string(),
+        })
+    }
+
+    async fn languages(&self, project: &Project) -> Result<Vec<Language>> {
+        let Some(repository_size) = project.statistics.as_ref().map(|s| s.repository_size) else {
+            tracing::warn!(
+                "No statistics for GitLab project {}, so its language sizes are unknown; \
+                 skipping its languages (the token must belong to a project member)",
+                project.path_with_namespace
+            );
+            return Ok(vec![]);
+        };
+        let url = format!("{}/api/v4/projects/{}/languages", self.base_url, project.id);
+        let (_, body) = rest_get_with_retry(&self.client, &url).await?;
+        let percentages: HashMap<String, f64> = serde_json::from_str(&body)?;
+        Ok(percentages
+            .into_iter()
+            .map(|(name, pct)| Language {
+                name,
+                color: None,
+                bytes: (repository_size as f64 * pct / 100.0).round() as i64,
+            })
+            .collect())
+    }
+
+    async fn count(&self, path_and_query: &str) -> Result<i64> {
+        let url = format!("{}{}", self.base_url, path_and_query);
+        let (headers, _) = rest_get_with_retry(&self.client, &url).await?;
+        header_i64(&headers, "x-total")
+            .ok_or_else(|| anyhow::anyhow!("No X-Total header in response from {}", url))
+    }
+}
+
+#[async_trait]
+impl Forge for GitLabForge {
+    fn name(&self) -> &str {
+        "GitLab"
+    }
+
+    async fn user_repos(&self) -> Result<User> {
+        tracing::info!("Getting GitLab projects for login {} from {}", self.login, self.base_url);
+        let mut user = User::default();
+        let mut page = 1;
+        loop {
+            let url = format!(
+                "{}/api/v4/users/{}/projects?statistics=true&per_page={}&page={}",
+                self.base_url, self.login, PER_PAGE, page
+            );
+            let (headers, body) = rest_get_with_retry(&self.client, &url).await?;
+            let projects: Vec<Project> = serde_json::from_str(&body)?;
+            for project in projects {
+                let languages = self.languages(&project).await?;
+                user.repos.push(Repo {
+                    full_name: project.path_with_namespace,
+                    url: project.web_url,
+                    stargazer_count: project.star_count,
+                    fork_count: project.forks_count,
+                    created_at: project.created_at,
+                    pushed_at: project.last_activity_at,
+                    recent_commits: vec![],
+                    last_commit_at: None,
+                    description: project.description.filter(|d| !d.is_empty()),
+                    homepage_url: None,
+                    primary_language: None,
+                    open_graph_image_url: None,
+                    languages,
+                    is_archived: project.archived,
+                    is_disabled: false,
+                    is_empty: project.empty_repo,
+                    is_fork: project.forked_from_project.is_some(),
+                    is_mirror: project.mirror,
+                    is_private: project.visibility.as_deref().is_some_and(|v| v != "public"),
+                });
+            }
+            match header_i64(&headers, "x-next-page") {
+                Some(next) => page = next,
+                None => break,
+            }
+        }
+        Ok(user)
+    }
+
+    async fn issue_and_pr_stats(&self) -> Result<IssueAndPrStats> {
+        tracing::info!("Getting GitLab issue and merge request data for {}", self.login);
+        let url = format!(
+            "{}/api/v4/issues_statistics?author_username={}&scope=all",
+            self.base_url, self.login
+        );
+        let (_, body) = rest_get_with_retry(&self.client, &url).await?;
+        let issues: IssuesStatistics = serde_json::from_str(&body)?;
+
+        let mrs = format!("/api/v4/merge_requests?author_username={}&scope=all&per_page=1", self.login);
+        Ok(IssueAndPrStats {
+            issues_created: issues.statistics.counts.all,
+            issues_closed: issues.statistics.counts.closed,
+            prs_created: self.count(&mrs).await?,
+            prs_merged: self.count(&format!("{mrs}&state=merged")).await?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::forge::stub::StubServer;
+
+    #[tokio::test]
+    async fn user_repos_estimates_language_bytes() {
+        let projects = r#"[{"id":42,"path_with_namespace":"andreasom/omt","web_url":"https://gitlab.com/andreasom/omt",
+            "star_count":3,"forks_count":1,"created_at":"2020-01-01T00:00:00.000Z",
+            "last_activity_at":"2024-05-01T00:00:00.000Z","archived":false,"empty_repo":false,
+            "visibility":"public","statistics":{"repository_size":2000}}]"#;
+        let server = StubServer::start(vec![
+            ("/api/v4/users/andreasom/projects", vec![], projects.to_string()),
+            ("/api/v4/projects/42/languages", vec![], r#"{"Rust":75.0,"Shell":25.0}"#.to_string()),
+        ]);
+        let forge = GitLabForge::new(&server.url(""), "andreasom", Some("token"), "test").unwrap();
+
+        let user = forge.user_repos().await.unwrap();
+
+        assert_eq!(user.repos.len(), 1);
+        let repo = &user.repos[0];
+        assert_eq!(repo.full_name, "andreasom/omt");
+        assert!(!repo.is_fork);
+        assert!(!repo.is_private);
+        let rust = repo.languages.iter().find(|l| l.name == "Rust").unwrap();
+        assert_eq!(rust.bytes, 1500);
+    }
+
+    #[tokio::test]
+    async fn user_repos_without_statistics_have_no_languages() {
+        let projects = r#"[{"id":42,"path_with_namespace":"andreasom/omt","web_url":"https://gitlab.com/andreasom/omt",
+            "star_count":3,"forks_count":1,"created_at":"2020-01-01T00:00:00.000Z",
+            "last_activity_at":"2024-05-01T00:00:00.000Z","visibility":"public"}]"#;
+        let server = StubServer::start(vec![
+            ("/api/v4/users/andreasom/projects", vec![], projects.to_string()),
+            ("/api/v4/projects/42/languages", vec![], r#"{"Rust":75.0,"Shell":25.0}"#.to_string()),
+        ]);
+        let forge = GitLabForge::new(&server.url(""), "andreasom", None, "test").unwrap();
+
+        let user = forge.user_repos().await.unwrap();
+
+        assert_eq!(user.repos.len(), 1);
+        assert_eq!(user.repos[0].stargazer_count, 3);
+        assert!(user.repos[0].languages.is_empty());
+        assert!(!server.requests().iter().any(|r| r.contains("/languages")));
+    }
+
+    #[tokio::test]
+    async fn issue_and_pr_stats_uses_statistics_and_totals() {
+        let server = StubServer::start(vec![
+            (
+                "/api/v4/issues_statistics",
+                vec![],
+                r#"{"statistics":{"counts":{"all":5,"closed":4,"opened":1}}}"#.to_string(),
+            ),
+            (
+                "/api/v4/merge_requests?author_username=andreasom&scope=all&per_page=1&state=merged",
+                vec![("X-Total", "2".to_string())],
+                "[]".to_string(),
+            ),
+            ("/api/v4/merge_requests", vec![("X-Total", "3".to_string())], "[]".to_string()),
+        ]);
+        let forge = GitLabForge::new(&server.url(""), "andreasom", None, "test").unwrap();
+
+        let stats = forge.issue_and_pr_stats().await.unwrap();
+
+        assert_eq!(stats.issues_created, 5);
+        assert_eq!(stats.issues_closed, 4);
+        assert_eq!(stats.prs_created, 3);
+        assert_eq!(stats.prs_merged, 2);
+    }
+}
@@ -0,0 +1,512 @@
+// Forge providers yield normalized repo records and issue/PR counts, so the
+// stats code doesn't care where the data came from.
+mod gitea;
+pub mod github;
+mod gitlab;
+#[cfg(test)]
+mod stub;
+
+pub use gitea::GiteaForge;
+pub use github::GitHubForge;
+pub use gitlab::GitLabForge;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use regex::RegexSet;
+use reqwest::{
+    header::{HeaderMap, RETRY_AFTER},
+    Client, StatusCode,
+};
+use std::{collections::HashMap, env};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+use crate::{IssueAndPrStats, RepoStars};
+
+const GITLAB_DEFAULT_URL: &str = "https://gitlab.com";
+
+const MAX_RETRIES: u32 = 4;
+const INITIAL_DELAY_MS: u64 = 1000;
+
+// How many requests may be in flight at once, across all forges and data
+// sources. Can be overridden with MAX_CONCURRENT_REQUESTS.
+const DEFAULT_MAX_CONCURRENT_REQUESTS: usize = 4;
+
+// Every request to a forge takes a permit from this, so fetching data sources
+// concurrently doesn't burn through the rate limits any faster than the
+// configured number of parallel requests allows.
+static REQUEST_PERMITS: Lazy<Semaphore> = Lazy::new(|| {
+    let permits = max_concurrent_requests();
+    tracing::debug!("Allowing {} concurrent requests", permits);
+    Semaphore::new(permits)
+});
+
+fn max_concurrent_requests() -> usize {
+    match env::var("MAX_CONCURRENT_REQUESTS") {
+        Ok(v) => match v.parse::<usize>() {
+            Ok(n) if n > 0 => n,
+            _ => {
+                tracing::warn!(
+                    "Invalid MAX_CONCURRENT_REQUESTS `{}`; using {}",
+                    v,
+                    DEFAULT_MAX_CONCURRENT_REQUESTS
+                );
+                DEFAULT_MAX_CONCURRENT_REQUESTS
+            }
+        },
+        Err(_) => DEFAULT_MAX_CONCURRENT_REQUESTS,
+    }
+}
+
+// Commits by authors matching any of these (case-insensitive) don't count as
+// activity on a repo, so dependency bumps don't make a dormant repo look
+// recent. More can be added with `ClientBuilder::bot_authors`.
+pub const DEFAULT_BOT_AUTHOR_PATTERNS: &[&str] = &[
+    r"\[bot\]",
+    r"^dependabot\b",
+    r"^renovate\b",
+    r"^github-actions\b",
+    r"^greenkeeper\b",
+    r"^snyk-bot\b",
+];
+
+/// `DEFAULT_BOT_AUTHOR_PATTERNS` and `extra` as one case-insensitive set.
+pub fn bot_authors(extra: &[impl AsRef<str>]) -> Result<RegexSet> {
+    let patterns = DEFAULT_BOT_AUTHOR_PATTERNS
+        .iter()
+        .copied()
+        .chain(extra.iter().map(AsRef::as_ref))
+        .map(|p| format!("(?i){p}"));
+    RegexSet::new(patterns).map_err(|e| anyhow::anyhow!("Invalid bot author pattern: {e}"))
+}
+
+#[derive(Debug, Clone)]
+pub struct Language {
+    pub name: String,
+    pub color: Option<String>,
+    pub bytes: i64,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Repo {
+    pub full_name: String,
+    pub url: String,
+    pub stargazer_count: i64,
+    pub fork_count: i64,
+    pub created_at: Option<String>,
+    pub pushed_at: Option<String>,
+    // The latest commits on the default branch, newest first. Empty when the
+    // forge doesn't tell.
+    pub recent_commits: Vec<Commit>,
+    // The latest of `recent_commits` not authored by a bot, see
+    // `set_last_human_commits`. Unlike `pushed_at` this isn't bumped by bots
+    // or by pushes to other branches.
+    pub last_commit_at: Option<String>,
+    pub description: Option<String>,
+    pub homepage_url: Option<String>,
+    // The name of the main language, as the forge sees it.
+    pub primary_language: Option<String>,
+    // The image shown when the repo is linked, e.g. on social media.
+    pub open_graph_image_url: Option<String>,
+    pub languages: Vec<Language>,
+    pub is_archived: bool,
+    pub is_disabled: bool,
+    pub is_empty: bool,
+    pub is_fork: bool,
+    pub is_mirror: bool,
+    pub is_private: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Commit {
+    pub committed_at: String,
+    // The login, name and email of the author, as far as they're known.
+    // Empty when the author is unknown.
+    pub author: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct User {
+    pub created_at: Option<String>,
+    pub repos: Vec<Repo>,
+}
+
+#[async_trait]
+pub trait Forge: Send + Sync {
+    fn name(&self) -> &str;
+    /// Returns the public repos owned by the configured login.
+    async fn user_repos(&self) -> Result<User>;
+    async fn issue_and_pr_stats(&self) -> Result<IssueAndPrStats>;
+    /// Returns when the stars of each owned repo were given. Forges that
+    /// don't record that have no star history.
+    async fn star_history(&self) -> Result<Vec<RepoStars>> {
+        Ok(vec![])
+    }
+}
+
+/// Builds the providers for the forges configured via env vars, in addition
+/// to GitHub which is always queried:
+///
+/// - `GITLAB_LOGIN` enables GitLab, with `GITLAB_URL` (defaults to
+///   `https://gitlab.com`) and an optional `GITLAB_TOKEN`.
+/// - `GITEA_LOGIN` and `GITEA_URL` enable a Gitea or Forgejo instance, with an
+///   optional `GITEA_TOKEN`.
+pub fn extra_forges_from_env(user_agent: &str) -> Result<Vec<Box<dyn Forge>>> {
+    let mut forges: Vec<Box<dyn Forge>> = vec![];
+    if let Ok(login) = env::var("GITLAB_LOGIN") {
+        let base_url = env::var("GITLAB_URL").unwrap_or_else(|_| GITLAB_DEFAULT_URL.to_string());
+        let token = env::var("GITLAB_TOKEN").ok();
+        forges.push(Box::new(GitLabForge::new(&base_url, &login, token.as_deref(), user_agent)?));
+    }
+    if let Ok(login) = env::var("GITEA_LOGIN") {
+        let base_url = env::var("GITEA_URL")
+            .map_err(|_| anyhow::anyhow!("GITEA_URL must be set when GITEA_LOGIN is set"))?;
+        let token = env::var("GITEA_TOKEN").ok();
+        forges.push(Box::new(GiteaForge::new(&base_url, &login, token.as_deref(), user_agent)?));
+    }
+    Ok(forges)
+}
+
+/// Merges repos mirrored across forges into one record. Repos with the same
+/// name without the owner are merged when one of them is a mirror, or when
+/// the url or homepage of one points at the other; others are different
+/// projects that happen to share a name. The first record wins for the url,
+/// the flags and the language data (so bytes aren't counted twice), unless
+/// it's archived, disabled, empty, private or a fork and the other isn't.
+/// Stars and forks are summed, and the most recent push and commit are kept.
+pub fn merge_mirrored_repos(repos: Vec<Repo>) -> Vec<Repo> {
+    let mut merged: Vec<Repo> = Vec::with_capacity(repos.len());
+    let mut by_name: HashMap<String, Vec<usize>> = HashMap::new();
+    for repo in repos {
+        let same_name = by_name.entry(short_name(&repo.full_name).to_lowercase()).or_default();
+        match same_name.iter().copied().find(|&i| are_mirrors(&merged[i], &repo)) {
+            Some(i) => merge_repo(&mut merged[i], repo),
+            None => {
+                same_name.push(merged.len());
+                merged.push(repo);
+            }
+        }
+    }
+    merged
+}
+
+fn are_mirrors(a: &Repo, b: &Repo) -> bool {
+    let points_at = |from: &Repo, to: &Repo| {
+        same_url(&from.url, &to.url) || from.homepage_url.as_deref().is_some_and(|url| same_url(url, &to.url))
+    };
+    a.is_mirror || b.is_mirror || points_at(a, b) || points_at(b, a)
+}
+
+fn same_url(a: &str, b: &str) -> bool {
+    let normalize = |url: &str| {
+        let url = url.trim().to_lowercase();
+        let url = url.trim_start_matches("https://").trim_start_matches("http://");
+        url.trim_end_matches('/').trim_end_matches(".git").to_string()
+    };
+    let a = normalize(a);
+    !a.is_empty() && a == normalize(b)
+}
+
+// Whether the repo is left out of the listings or counted as someone else's.
+fn is_hidden(repo: &Repo) -> bool {
+    repo.is_archived || repo.is_disabled || repo.is_empty || repo.is_private || repo.is_fork
+}
+
+fn merge_repo(existing: &mut Repo, mut repo: Repo) {
+    if is_hidden(existing) && !is_hidden(&repo) {
+        std::mem::swap(existing, &mut repo);
+    }
+    tracing::debug!("Merging {} into {}", repo.full_name, existing.full_name);
+    existing.stargazer_count += repo.stargazer_count;
+    existing.fork_count += repo.fork_count;
+    // Merged with what it mirrors, it's no longer someone else's project.
+    existing.is_mirror &= repo.is_mirror;
+    keep_date(&mut existing.pushed_at, repo.pushed_at, |new, old| new > old);
+    keep_date(&mut existing.last_commit_at, repo.last_commit_at, |new, old| new > old);
+    // The mirror can't be older than what it mirrors.
+    keep_date(&mut existing.created_at, repo.created_at, |new, old| new < old);
+    if existing.languages.is_empty() {
+        existing.languages = repo.languages;
+    }
+    existing.description = existing.description.take().or(repo.description);
+    existing.homepage_url = existing.homepage_url.take().or(repo.homepage_url);
+    existing.primary_language = existing.primary_language.take().or(repo.primary_language);
+    existing.open_graph_image_url = existing.open_graph_image_url.take().or(repo.open_graph_image_url);
+}
+
+/// Sets `last_commit_at` of the `repos` with recent commits to the latest one
+/// whose author doesn't match `bots`. Commits by unknown authors don't count
+/// either. When none of the recent commits count, the last human commit is
+/// older than all of them, so the oldest one fetched is used as an upper
+/// bound. The creation date would be a lower bound, possibly years off.
+pub fn set_last_human_commits(repos: &mut [Repo], bots: &RegexSet) {
+    for repo in repos.iter_mut().filter(|r| !r.recent_commits.is_empty()) {
+        let human = repo
+            .recent_commits
+            .iter()
+            .find(|c| !c.author.is_empty() && !c.author.iter().any(|id| bots.is_match(id)));
+        repo.last_commit_at = match human {
+            Some(commit) => Some(commit.committed_at.clone()),
+            None => {
+                tracing::info!(
+                    "The last {} commits to {} are all by bots; going by the oldest of them",
+                    repo.recent_commits.len(),
+                    repo.full_name
+                );
+                repo.recent_commits.last().map(|c| c.committed_at.clone())
+            }
+        };
+    }
+}
+
+/// Replaces `existing` with `new` if `new` is a date and `existing` isn't, or
+/// if `wins` says so. Dates are compared as instants, as forges format them
+/// differently (fractional seconds, offsets).
+fn keep_date(existing: &mut Option<String>, new: Option<String>, wins: fn(DateTime<Utc>, DateTime<Utc>) -> bool) {
+    let parse = |date: &Option<String>| {
+        let date = DateTime::parse_from_rfc3339(date.as_deref()?).ok()?;
+        Some(date.with_timezone(&Utc))
+    };
+    let replace = match (parse(&new), parse(existing)) {
+        (Some(new), Some(old)) => wins(new, old),
+        (Some(_), None) => true,
+        (None, _) => false,
+    };
+    if replace {
+        *existing = new;
+    }
+}
+
+fn short_name(full_name: &str) -> &str {
+    full_name.rsplit('/').next().unwrap_or(full_name)
+}
+
+pub(crate) async fn acquire_request_permit() -> Result<SemaphorePermit<'static>> {
+    Ok(REQUEST_PERMITS.acquire().await?)
+}
+
+/// GETs `url` and returns the response headers and body, retrying with
+/// exponential backoff on transport errors, server errors and rate limits.
+/// Other client errors, like a bad token or a missing repo, fail right away.
+pub(crate) async fn rest_get_with_retry(client: &Client, url: &str) -> Result<(HeaderMap, String)> {
+    for attempt in 1..=MAX_RETRIES {
+        tracing::debug!("REST request attempt {}/{} for {}", attempt, MAX_RETRIES, url);
+
+        let error = {
+            let _permit = acquire_request_permit().await?;
+            match client.get(url).send().await {
+                Ok(response) => {
+                    let status = response.status();
+                    let headers = response.headers().clone();
+                    match response.text().await {
+                        Ok(text) if status.is_success() => return Ok((headers, text)),
+                        Ok(text) if !is_retryable(status, &headers) => {
+                            return Err(anyhow::anyhow!("REST request to {url} failed: HTTP status {status}, body: {text}"))
+                        }
+                        Ok(text) => format!("HTTP status {status}, body: {text}"),
+                        Err(e) => format!("Failed to read response body: {e}"),
+                    }
+                }
+                Err(e) => format!("HTTP request failed: {e}"),
+            }
+        };
+
+        tracing::error!("REST request attempt {}/{} failed: {}", attempt, MAX_RETRIES, error);
+        if attempt < MAX_RETRIES {
+            let delay_ms = INITIAL_DELAY_MS * 2u64.pow(attempt - 1);
+            tracing::warn!("Retrying in {}ms...", delay_ms);
+            tokio::time::sleep(tokio::time::Duration::from_millis(delay_ms)).await;
+        } else {
+            tracing::error!("All {} retry attempts exhausted", MAX_RETRIES);
+            return Err(anyhow::anyhow!(
+                "REST request to {} failed after {} attempts: {}",
+                url,
+                MAX_RETRIES,
+                error
+            ));
+        }
+    }
+
+    unreachable!("Loop should always return before reaching here");
+}
+
+// Server errors may go away by themselves, and rate limits do. GitHub and
+// Gitea answer 403 when rate limited, which tells from a bad token only by
+// the rate limit headers.
+fn is_retryable(status: StatusCode, headers: &HeaderMap) -> bool {
+    let rate_limited = || {
+        headers.contains_key(RETRY_AFTER)
+            || ["x-ratelimit-remaining", "ratelimit-remaining"]
+                .iter()
+                .any(|name| header_i64(headers, name) == Some(0))
+    };
+    status.is_server_error()
+        || status == StatusCode::TOO_MANY_REQUESTS
+        || (status == StatusCode::FORBIDDEN && rate_limited())
+}
+
+pub(crate) fn header_i64(headers: &HeaderMap, name: &str) -> Option<i64> {
+    headers.get(name)?.to_str().ok()?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use stub::StubServer;
+
+    fn repo(full_name: &str, created_at: Option<&str>, pushed_at: Option<&str>) -> Repo {
+        Repo {
+            full_name: full_name.to_string(),
+            url: format!("https://example.com/{full_name}"),
+            stargazer_count: 1,
+            created_at: created_at.map(str::to_string),
+            pushed_at: pushed_at.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn mirror(full_name: &str, created_at: Option<&str>, pushed_at: Option<&str>) -> Repo {
+        Repo { is_mirror: true, ..repo(full_name, created_at, pushed_at) }
+    }
+
+    #[test]
+    fn mirrors_are_merged_by_comparing_instants() {
+        let merged = merge_mirrored_repos(vec![
+            repo("AndreasOM/omt", Some("2020-01-01T12:00:00Z"), Some("2024-05-01T10:00:00Z")),
+            // GitLab: fractional seconds; 11:00 is later although it sorts first.
+            mirror("andreasom/omt", Some("2020-01-01T11:00:00.000Z"), Some("2024-05-01T11:00:00.000Z")),
+            // Gitea: an offset; 11:30 UTC is later still, 10:30 UTC is older.
+            mirror("om/omt", Some("2020-01-01T12:30:00+02:00"), Some("2024-05-01T13:30:00+02:00")),
+            mirror("om2/omt", None, None),
+        ]);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].stargazer_count, 4);
+        assert_eq!(merged[0].created_at.as_deref(), Some("2020-01-01T12:30:00+02:00"));
+        assert_eq!(merged[0].pushed_at.as_deref(), Some("2024-05-01T13:30:00+02:00"));
+    }
+
+    #[test]
+    fn repos_that_only_share_a_name_are_kept_apart() {
+        let merged = merge_mirrored_repos(vec![
+            Repo { is_archived: true, ..repo("AndreasOM/foo", None, None) },
+            Repo { is_fork: true, ..repo("AndreasOM/bar", None, None) },
+            repo("om/foo", None, None),
+            repo("om/bar", None, None),
+        ]);
+        let names: Vec<_> = merged.iter().map(|r| (r.full_name.as_str(), r.stargazer_count)).collect();
+        assert_eq!(names, [("AndreasOM/foo", 1), ("AndreasOM/bar", 1), ("om/foo", 1), ("om/bar", 1)]);
+        // Unless one says it's the other.
+        let merged = merge_mirrored_repos(vec![
+            repo("AndreasOM/foo", None, None),
+            Repo { homepage_url: Some("https://example.com/AndreasOM/foo/".to_string()), ..repo("om/foo", None, None) },
+        ]);
+        assert_eq!(merged.len(), 1);
+    }
+
+    #[test]
+    fn hidden_repos_dont_swallow_listed_mirrors() {
+        let merged = merge_mirrored_repos(vec![
+            Repo { is_archived: true, ..repo("AndreasOM/foo", None, None) },
+            Repo { stargazer_count: 2, ..mirror("om/foo", None, None) },
+            Repo { is_private: true, ..repo("AndreasOM/bar", None, None) },
+            Repo { languages: vec![Language { name: "Rust".to_string(), color: None, bytes: 100 }], ..mirror("om/bar", None, None) },
+        ]);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].url, "https://example.com/om/foo");
+        assert_eq!(merged[0].stargazer_count, 3);
+        assert!(!merged[0].is_archived && !merged[0].is_mirror);
+        assert_eq!(merged[1].full_name, "om/bar");
+        assert!(!merged[1].is_private);
+        assert_eq!(merged[1].languages.len(), 1);
+    }
+
+    #[test]
+    fn missing_dates_are_filled_from_mirrors() {
+        let merged = merge_mirrored_repos(vec![
+            repo("AndreasOM/omt", None, None),
+            mirror("andreasom/omt", Some("2020-01-01T00:00:00Z"), Some("2024-05-01T00:00:00Z")),
+        ]);
+        assert_eq!(merged[0].created_at.as_deref(), Some("2020-01-01T00:00:00Z"));
+        assert_eq!(merged[0].pushed_at.as_deref(), Some("2024-05-01T00:00:00Z"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+    async fn requests_in_flight_are_limited() {
+        let server = StubServer::start_with_delay(vec![("/", vec![], "{}".to_string())], Duration::from_millis(50));
+        let client = Client::new();
+        let urls: Vec<_> = (0..12).map(|i| server.url(&format!("/{i}"))).collect();
+
+        let results = futures::future::join_all(urls.iter().map(|url| rest_get_with_retry(&client, url))).await;
+
+        assert!(results.iter().all(|r| r.is_ok()));
+        assert_eq!(server.requests().len(), 12);
+        let limit = max_concurrent_requests();
+        assert!(server.max_in_flight() <= limit, "{} > {}", server.max_in_flight(), limit);
+        // Other tests share the permits, but they don't hold on to them for long.
+        assert!(server.max_in_flight() >= limit.min(2), "requests weren't concurrent");
+    }
+
+    #[tokio::test]
+    async fn client_errors_arent_retried() {
+        let server = StubServer::start(vec![]);
+        let error = rest_get_with_retry(&Client::new(), &server.url("/missing")).await.unwrap_err();
+        assert!(error.to_string().contains("404"), "{error}");
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[test]
+    fn retryable_statuses() {
+        let headers = |pairs: &[(&'static str, &str)]| {
+            pairs.iter().map(|(name, value)| (name.parse().unwrap(), value.parse().unwrap())).collect::<HeaderMap>()
+        };
+        let none = headers(&[]);
+        assert!(is_retryable(StatusCode::BAD_GATEWAY, &none));
+        assert!(is_retryable(StatusCode::TOO_MANY_REQUESTS, &none));
+        assert!(!is_retryable(StatusCode::UNAUTHORIZED, &none));
+        assert!(!is_retryable(StatusCode::NOT_FOUND, &none));
+        assert!(!is_retryable(StatusCode::FORBIDDEN, &none));
+        assert!(!is_retryable(StatusCode::FORBIDDEN, &headers(&[("x-ratelimit-remaining", "4999")])));
+        assert!(is_retryable(StatusCode::FORBIDDEN, &headers(&[("x-ratelimit-remaining", "0")])));
+        assert!(is_retryable(StatusCode::FORBIDDEN, &headers(&[("ratelimit-remaining", "0")])));
+        assert!(is_retryable(StatusCode::FORBIDDEN, &headers(&[("retry-after", "60")])));
+    }
+
+    fn commit(committed_at: &str, author: &[&str]) -> Commit {
+        Commit {
+            committed_at: committed_at.to_string(),
+            author: author.iter().map(|a| a.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn last_human_commits_skip_bots_and_unknown_authors() {
+        let bots = bot_authors(&["^murderbot$"]).unwrap();
+        let with_commits = |recent_commits| Repo {
+            recent_commits,
+            ..repo("AndreasOM/omt", Some("2020-01-01T00:00:00Z"), Some("2024-06-01T00:00:00Z"))
+        };
+        let mut repos = [
+            with_commits(vec![
+                commit("2024-06-01T00:00:00Z", &["dependabot[bot]", "49699333+dependabot[bot]@users.noreply.github.com"]),
+                commit("2024-05-20T00:00:00Z", &["Renovate Bot", "bot@renovateapp.com"]),
+                commit("2024-05-10T00:00:00Z", &[]),
+                commit("2024-05-01T00:00:00Z", &["Murderbot", "murderbot@example.com"]),
+                commit("2023-11-02T00:00:00Z", &["AndreasOM", "Andreas", "andreas@example.com"]),
+            ]),
+            // All bots, so the human commit is older than the oldest of them.
+            with_commits(vec![
+                commit("2024-06-01T00:00:00Z", &["dependabot[bot]"]),
+                commit("2024-05-01T00:00:00Z", &["github-actions[bot]"]),
+            ]),
+            // No commits known, like on GitLab.
+            with_commits(vec![]),
+        ];
+        set_last_human_commits(&mut repos, &bots);
+        assert_eq!(repos[0].last_commit_at.as_deref(), Some("2023-11-02T00:00:00Z"));
+        assert_eq!(repos[1].last_commit_at.as_deref(), Some("2024-05-01T00:00:00Z"));
+        assert_eq!(repos[2].last_commit_at, None);
+        assert!(bot_authors(&["("]).is_err());
+    }
+}
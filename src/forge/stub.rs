@@ -0,0 +1,124 @@
+// A tiny HTTP server for testing the forge providers without network access.
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+/// A canned response: the request target prefix it answers, extra response
+/// headers, and the body. The first route whose prefix matches wins. The
+/// prefix may be followed by a space and text the request body must contain,
+/// e.g. `/graphql "operationName":"StargazersQuery"`.
+pub(crate) type Route = (&'static str, Vec<(&'static str, String)>, String);
+
+pub(crate) struct StubServer {
+    port: u16,
+    requests: Arc<Mutex<Vec<String>>>,
+    max_in_flight: Arc<AtomicUsize>,
+}
+
+impl StubServer {
+    pub(crate) fn start(routes: Vec<Route>) -> Self {
+        Self::start_with_delay(routes, Duration::ZERO)
+    }
+
+    /// Like `start`, but every response is held back for `delay`, so
+    /// concurrent requests overlap.
+    pub(crate) fn start_with_delay(routes: Vec<Route>, delay: Duration) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind stub server");
+        let port = listener.local_addr().expect("stub server address").port();
+        let requests = Arc::new(Mutex::new(vec![]));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let seen = Arc::clone(&requests);
+        let max = Arc::clone(&max_in_flight);
+        let routes = Arc::new(routes);
+        thread::spawn(move || {
+            let in_flight = Arc::new(AtomicUsize::new(0));
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { break };
+                let (routes, seen, max, in_flight) =
+                    (Arc::clone(&routes), Arc::clone(&seen), Arc::clone(&max), Arc::clone(&in_flight));
+                thread::spawn(move || {
+                    let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    max.fetch_max(now, Ordering::SeqCst);
+                    respond(stream, &routes, &seen, delay, &in_flight);
+                });
+            }
+        });
+        Self {
+            port,
+            requests,
+            max_in_flight,
+        }
+    }
+
+    pub(crate) fn url(&self, path: &str) -> String {
+        format!("http://127.0.0.1:{}{}", self.port, path)
+    }
+
+    pub(crate) fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// The most requests that were being answered at the same time.
+    pub(crate) fn max_in_flight(&self) -> usize {
+        self.max_in_flight.load(Ordering::SeqCst)
+    }
+}
+
+// Answers one request. `in_flight` is decremented before the response is
+// sent, as the client may send its next request as soon as it has it.
+fn respond(mut stream: TcpStream, routes: &[Route], seen: &Mutex<Vec<String>>, delay: Duration, in_flight: &AtomicUsize) {
+    let mut reader = BufReader::new(stream.try_clone().expect("clone stream"));
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line).is_err() {
+        in_flight.fetch_sub(1, Ordering::SeqCst);
+        return;
+    }
+    let mut content_length = 0;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line).is_err() || line.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+    let mut body = vec![0; content_length];
+    let _ = reader.read_exact(&mut body);
+    let body = String::from_utf8_lossy(&body);
+
+    let target = request_line.split_whitespace().nth(1).unwrap_or("").to_string();
+    seen.lock().unwrap().push(target.clone());
+    thread::sleep(delay);
+
+    let matches = |route: &str| {
+        let (prefix, needle) = route.split_once(' ').unwrap_or((route, ""));
+        target.starts_with(prefix) && body.contains(needle)
+    };
+    let response = match routes.iter().find(|(route, _, _)| matches(route)) {
+        Some((_, headers, body)) => {
+            let mut response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+                body.len()
+            );
+            for (name, value) in headers {
+                response.push_str(&format!("{name}: {value}\r\n"));
+            }
+            response.push_str("\r\n");
+            response.push_str(body);
+            response
+        }
+        None => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string(),
+    };
+    in_flight.fetch_sub(1, Ordering::SeqCst);
+    let _ = stream.write_all(response.as_bytes());
+}
@@ -0,0 +1,245 @@
+// TinyTemplate formatters for Markdown output. TinyTemplate escapes values
+// for HTML by default, which does nothing for Markdown, where a `_` or `*` in
+// a repo name starts emphasis and a `]` ends a link text. Values are
+// Markdown-escaped instead, and the named formatters cover the rest:
+//
+// - `{value | unescaped}` for pre-rendered Markdown, like the Mermaid charts
+// - `{count | compact}`: 1234 becomes `1.2k`
+// - `{date | relative_date}`: a date or RFC 3339 time becomes `3 days ago`
+// - `{percentage | percent_bar}`: a bar of Unicode blocks, like `███▌░░░░░░`
+// - `{count | plural}`: `s` unless the count is one, as in
+//   `{n} star{n | plural}`
+// - `{value | table_cell}`: escaped for a table cell, with line breaks kept
+// - `{url | url}`: for link targets, which must not be Markdown-escaped
+use chrono::{DateTime, NaiveDate, Utc};
+use serde_json::Value;
+use std::fmt::Write;
+use tinytemplate::{
+    error::{Error, Result},
+    TinyTemplate,
+};
+
+// The characters that can start or end inline Markdown (or GFM) syntax in
+// the middle of a line.
+const MARKDOWN_SPECIAL: &[char] = &['\\', '`', '*', '_', '[', ']', '<', '>', '|', '~', '&'];
+
+const PERCENT_BAR_WIDTH: i64 = 10;
+// Blocks filled to 1/8 up to 7/8 of the width of a cell.
+const PARTIAL_BLOCKS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// Makes Markdown escaping the default, and registers the named formatters.
+/// Relative dates are relative to `now`.
+pub fn register(tt: &mut TinyTemplate<'_>, now: DateTime<Utc>) {
+    tt.set_default_formatter(&markdown);
+    tt.add_formatter("unescaped", tinytemplate::format_unescaped);
+    tt.add_formatter("compact", compact);
+    tt.add_formatter("relative_date", move |value, output| relative_date(value, output, now));
+    tt.add_formatter("percent_bar", percent_bar);
+    tt.add_formatter("plural", plural);
+    tt.add_formatter("table_cell", table_cell);
+    tt.add_formatter("url", url);
+}
+
+/// Escapes strings for Markdown text. Everything else is formatted as usual.
+pub fn markdown(value: &Value, output: &mut String) -> Result<()> {
+    match value {
+        Value::String(s) => {
+            escape(s, output);
+            Ok(())
+        }
+        _ => tinytemplate::format_unescaped(value, output),
+    }
+}
+
+pub(crate) fn escape(text: &str, output: &mut String) {
+    for c in text.chars() {
+        if MARKDOWN_SPECIAL.contains(&c) {
+            output.push('\\');
+        }
+        output.push(c);
+    }
+}
+
+pub fn compact(value: &Value, output: &mut String) -> Result<()> {
+    let n = number(value, "compact")?;
+    let (scaled, suffix) = match n.abs() {
+        a if a >= 1e9 => (n / 1e9, "B"),
+        a if a >= 1e6 => (n / 1e6, "M"),
+        a if a >= 1e3 => (n / 1e3, "k"),
+        _ => (n, ""),
+    };
+    // One decimal for small numbers, as in 1.2k, but none for 123k.
+    let text = if scaled.abs() < 100.0 && !suffix.is_empty() {
+        format!("{:.1}", (scaled * 10.0).trunc() / 10.0)
+    } else {
+        format!("{}", scaled.trunc())
+    };
+    write!(output, "{}{suffix}", text.strip_suffix(".0").unwrap_or(&text))?;
+    Ok(())
+}
+
+pub fn relative_date(value: &Value, output: &mut String, now: DateTime<Utc>) -> Result<()> {
+    let Value::String(s) = value else {
+        return Err(error("relative_date", value));
+    };
+    let date = parse_date(s).ok_or_else(|| error("relative_date", value))?;
+    let days = (now.naive_utc().date() - date).num_days();
+    let text = match days {
+        d if d <= 0 => "today".to_string(),
+        1 => "yesterday".to_string(),
+        d if d < 14 => format!("{d} days ago"),
+        d if d < 60 => format!("{} weeks ago", d / 7),
+        d if d < 365 => format!("{} months ago", d / 30),
+        d if d < 730 => "a year ago".to_string(),
+        d => format!("{} years ago", d / 365),
+    };
+    output.push_str(&text);
+    Ok(())
+}
+
+pub fn percent_bar(value: &Value, output: &mut String) -> Result<()> {
+    let percentage = number(value, "percent_bar")?.clamp(0.0, 100.0);
+    let eighths = (percentage * (PERCENT_BAR_WIDTH * 8) as f64 / 100.0).round() as i64;
+    let (full, partial) = (eighths / 8, eighths % 8);
+    for _ in 0..full {
+        output.push('█');
+    }
+    if partial > 0 {
+        output.push(PARTIAL_BLOCKS[partial as usize - 1]);
+    }
+    for _ in full + (partial > 0) as i64..PERCENT_BAR_WIDTH {
+        output.push('░');
+    }
+    Ok(())
+}
+
+pub fn plural(value: &Value, output: &mut String) -> Result<()> {
+    if number(value, "plural")?.abs() != 1.0 {
+        output.push('s');
+    }
+    Ok(())
+}
+
+pub fn table_cell(value: &Value, output: &mut String) -> Result<()> {
+    let mut escaped = String::new();
+    markdown(value, &mut escaped)?;
+    // A line break would end the row.
+    output.push_str(&escaped.trim().replace("\r\n", "<br>").replace('\n', "<br>"));
+    Ok(())
+}
+
+pub fn url(value: &Value, output: &mut String) -> Result<()> {
+    let Value::String(s) = value else {
+        return Err(error("url", value));
+    };
+    for c in s.chars() {
+        match c {
+            ' ' => output.push_str("%20"),
+            '(' => output.push_str("%28"),
+            ')' => output.push_str("%29"),
+            '<' => output.push_str("%3C"),
+            '>' => output.push_str("%3E"),
+            _ => output.push(c),
+        }
+    }
+    Ok(())
+}
+
+/// Parses a `YYYY-MM-DD` date or the date of an RFC 3339 time, in UTC.
+pub(crate) fn parse_date(s: &str) -> Option<NaiveDate> {
+    match DateTime::parse_from_rfc3339(s) {
+        Ok(d) => Some(d.with_timezone(&Utc).naive_utc().date()),
+        Err(_) => NaiveDate::parse_from_str(s, "%Y-%m-%d").ok(),
+    }
+}
+
+fn number(value: &Value, formatter: &str) -> Result<f64> {
+    value.as_f64().ok_or_else(|| error(formatter, value))
+}
+
+fn error(formatter: &str, value: &Value) -> Error {
+    Error::GenericError {
+        msg: format!("The {formatter} formatter can't format `{value}`"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn format(formatter: fn(&Value, &mut String) -> Result<()>, value: Value) -> String {
+        let mut output = String::new();
+        formatter(&value, &mut output).unwrap();
+        output
+    }
+
+    #[test]
+    fn markdown_escaping() {
+        assert_eq!(format(markdown, json!("my_repo [*wip*]")), r"my\_repo \[\*wip\*\]");
+        assert_eq!(format(markdown, json!("a|b & <c>")), r"a\|b \& \<c\>");
+        assert_eq!(format(markdown, json!(42)), "42");
+        assert_eq!(format(markdown, json!(null)), "");
+    }
+
+    #[test]
+    fn compact_numbers() {
+        let cases = [(7, "7"), (999, "999"), (1000, "1k"), (1234, "1.2k"), (12_345, "12.3k"), (123_456, "123k")];
+        for (n, expected) in cases {
+            assert_eq!(format(compact, json!(n)), expected);
+        }
+        assert_eq!(format(compact, json!(2_500_000)), "2.5M");
+        assert_eq!(format(compact, json!(-1500)), "-1.5k");
+    }
+
+    #[test]
+    fn relative_dates() {
+        let now = DateTime::parse_from_rfc3339("2024-06-15T12:00:00Z").unwrap().with_timezone(&Utc);
+        let relative = |date: &str| {
+            let mut output = String::new();
+            relative_date(&json!(date), &mut output, now).unwrap();
+            output
+        };
+        assert_eq!(relative("2024-06-15"), "today");
+        assert_eq!(relative("2024-06-14T23:59:59Z"), "yesterday");
+        assert_eq!(relative("2024-06-12"), "3 days ago");
+        assert_eq!(relative("2024-05-25"), "3 weeks ago");
+        assert_eq!(relative("2024-01-01"), "5 months ago");
+        assert_eq!(relative("2023-01-01"), "a year ago");
+        assert_eq!(relative("2020-06-01"), "4 years ago");
+        assert!(relative_date(&json!("last week"), &mut String::new(), now).is_err());
+    }
+
+    #[test]
+    fn percent_bars() {
+        assert_eq!(format(percent_bar, json!(0)), "░░░░░░░░░░");
+        assert_eq!(format(percent_bar, json!(35)), "███▌░░░░░░");
+        assert_eq!(format(percent_bar, json!(100)), "██████████");
+        assert_eq!(format(percent_bar, json!(250)), "██████████");
+    }
+
+    #[test]
+    fn plurals_cells_and_urls() {
+        assert_eq!(format(plural, json!(1)), "");
+        assert_eq!(format(plural, json!(0)), "s");
+        assert_eq!(format(plural, json!(2)), "s");
+        assert_eq!(format(table_cell, json!("Fast | small\nand _fun_ ")), r"Fast \| small<br>and \_fun\_");
+        assert_eq!(
+            format(url, json!("https://example.com/a_(b) c")),
+            "https://example.com/a_%28b%29%20c"
+        );
+    }
+
+    #[test]
+    fn registered_on_template() {
+        let mut tt = TinyTemplate::new();
+        register(&mut tt, Utc::now());
+        tt.add_template("t", "[{name}]({url | url}) {stars | compact} star{stars | plural}")
+            .unwrap();
+        let context = json!({"name": "my_repo", "url": "https://example.com/my_repo", "stars": 1200});
+        assert_eq!(
+            tt.render("t", &context).unwrap(),
+            r"[my\_repo](https://example.com/my_repo) 1.2k stars"
+        );
+    }
+}
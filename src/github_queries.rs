@@ -4,7 +4,7 @@ pub mod organization_repos_query {
     #![allow(dead_code)]
     use std::result::Result;
     pub const OPERATION_NAME: &str = "OrganizationReposQuery";
-    pub const QUERY : & str = "fragment repos on RepositoryConnection {\n  pageInfo {\n    endCursor\n    hasNextPage\n  }\n  nodes {\n    createdAt\n    pushedAt\n    forkCount\n    isArchived\n    isDisabled\n    isEmpty\n    isFork\n    isMirror\n    isPrivate\n    nameWithOwner\n    languages(first: 100) {\n      edges {\n        size\n      }\n      nodes {\n        color\n        name\n      }\n      totalSize\n    }\n    licenseInfo {\n      nickname\n      spdxId\n      name\n    }\n    owner {\n      __typename\n      login\n    }\n    stargazerCount\n    url\n  }\n}\n\nquery OrganizationReposQuery($login: String!, $after: String) {\n  organization(login: $login) {\n    repositories(\n      affiliations: [OWNER],\n      after: $after,\n      orderBy: {\n        direction: ASC,\n        field: NAME,\n      },\n      privacy: PUBLIC,\n    ) {\n      ...repos\n    }\n  }\n}\n\nquery UserReposQuery($login: String!, $after: String) {\n  user(login: $login) {\n    createdAt\n    repositories(\n      affiliations: [OWNER],\n      after: $after,\n      orderBy: {\n        direction: ASC,\n        field: NAME,\n      },\n      privacy: PUBLIC,\n    ) {\n      ...repos\n    }\n  }\n}\n\n# Adapted from queries in\n# https://github.com/lowlighter/metrics/blob/master/source/plugins/followup/querie/s\nquery IssuesAndPrsQuery {\n  issues_created:search(query: \"author:AndreasOM is:issue\", type: ISSUE, first: 0) {\n    issueCount\n  }\n  issues_closed:search(query: \"author:AndreasOM is:issue is:closed\", type: ISSUE, first: 0) {\n    issueCount\n  }\n  prs_created:search(query: \"author:AndreasOM is:pr\", type: ISSUE, first: 0) {\n    issueCount\n  }\n  prs_merged:search(query: \"author:AndreasOM is:pr is:merged\", type: ISSUE, first: 0) {\n    issueCount\n  }\n}\n" ;
+    pub const QUERY : & str = "fragment repos on RepositoryConnection {\n  pageInfo {\n    endCursor\n    hasNextPage\n  }\n  nodes {\n    createdAt\n    pushedAt\n    defaultBranchRef {\n      target {\n        __typename\n        ... on Commit {\n          history(first: 50) {\n            nodes {\n              committedDate\n              author {\n                email\n                name\n                user {\n                  login\n                }\n              }\n            }\n          }\n        }\n      }\n    }\n    forkCount\n    isArchived\n    isDisabled\n    isEmpty\n    isFork\n    isMirror\n    isPrivate\n    nameWithOwner\n    description\n    homepageUrl\n    openGraphImageUrl\n    primaryLanguage {\n      color\n      name\n    }\n    languages(first: 100) {\n      edges {\n        size\n      }\n      nodes {\n        color\n        name\n      }\n      totalSize\n    }\n    licenseInfo {\n      nickname\n      spdxId\n      name\n    }\n    owner {\n      __typename\n      login\n    }\n    stargazerCount\n    url\n  }\n}\n\nquery OrganizationReposQuery($login: String!, $after: String) {\n  organization(login: $login) {\n    repositories(\n      affiliations: [OWNER],\n      after: $after,\n      orderBy: {\n        direction: ASC,\n        field: NAME,\n      },\n      privacy: PUBLIC,\n    ) {\n      ...repos\n    }\n  }\n}\n\nquery UserReposQuery($login: String!, $after: String) {\n  user(login: $login) {\n    createdAt\n    repositories(\n      affiliations: [OWNER],\n      after: $after,\n      orderBy: {\n        direction: ASC,\n        field: NAME,\n      },\n      privacy: PUBLIC,\n    ) {\n      ...repos\n    }\n  }\n}\n\n# Adapted from queries in\n# https://github.com/lowlighter/metrics/blob/master/source/plugins/followup/querie/s\nquery IssuesAndPrsQuery {\n  issues_created:search(query: \"author:AndreasOM is:issue\", type: ISSUE, first: 0) {\n    issueCount\n  }\n  issues_closed:search(query: \"author:AndreasOM is:issue is:closed\", type: ISSUE, first: 0) {\n    issueCount\n  }\n  prs_created:search(query: \"author:AndreasOM is:pr\", type: ISSUE, first: 0) {\n    issueCount\n  }\n  prs_merged:search(query: \"author:AndreasOM is:pr is:merged\", type: ISSUE, first: 0) {\n    issueCount\n  }\n}\n\nquery StargazersQuery($owner: String!, $name: String!, $after: String) {\n  repository(owner: $owner, name: $name) {\n    stargazers(\n      first: 100,\n      after: $after,\n      orderBy: {\n        direction: ASC,\n        field: STARRED_AT,\n      },\n    ) {\n      pageInfo {\n        endCursor\n        hasNextPage\n      }\n      edges {\n        starredAt\n      }\n    }\n  }\n}\n\nquery StarredReposQuery($login: String!, $after: String) {\n  user(login: $login) {\n    repositories(\n      affiliations: [OWNER],\n      after: $after,\n      orderBy: {\n        direction: ASC,\n        field: NAME,\n      },\n      privacy: PUBLIC,\n    ) {\n      pageInfo {\n        endCursor\n        hasNextPage\n      }\n      nodes {\n        isArchived\n        isDisabled\n        isEmpty\n        isFork\n        isMirror\n        isPrivate\n        nameWithOwner\n        owner {\n          login\n        }\n        stargazerCount\n        url\n      }\n    }\n  }\n}\n" ;
     use super::*;
     use serde::{Deserialize, Serialize};
     #[allow(dead_code)]
@@ -42,6 +42,8 @@ pub mod organization_repos_query {
         pub created_at: DateTime,
         #[serde(rename = "pushedAt")]
         pub pushed_at: Option<DateTime>,
+        #[serde(rename = "defaultBranchRef")]
+        pub default_branch_ref: Option<ReposNodesDefaultBranchRef>,
         #[serde(rename = "forkCount")]
         pub fork_count: Int,
         #[serde(rename = "isArchived")]
@@ -58,6 +60,13 @@ pub mod organization_repos_query {
         pub is_private: Boolean,
         #[serde(rename = "nameWithOwner")]
         pub name_with_owner: String,
+        pub description: Option<String>,
+        #[serde(rename = "homepageUrl")]
+        pub homepage_url: Option<URI>,
+        #[serde(rename = "openGraphImageUrl")]
+        pub open_graph_image_url: URI,
+        #[serde(rename = "primaryLanguage")]
+        pub primary_language: Option<ReposNodesPrimaryLanguage>,
         pub languages: Option<ReposNodesLanguages>,
         #[serde(rename = "licenseInfo")]
         pub license_info: Option<ReposNodesLicenseInfo>,
@@ -67,6 +76,11 @@ pub mod organization_repos_query {
         pub url: URI,
     }
     #[derive(Deserialize, Debug)]
+    pub struct ReposNodesPrimaryLanguage {
+        pub color: Option<String>,
+        pub name: String,
+    }
+    #[derive(Deserialize, Debug)]
     pub struct ReposNodesLanguages {
         pub edges: Option<Vec<Option<ReposNodesLanguagesEdges>>>,
         pub nodes: Option<Vec<Option<ReposNodesLanguagesNodes>>>,
@@ -102,25 +116,40 @@ pub mod organization_repos_query {
         User,
     }
     #[derive(Deserialize, Debug)]
-    pub struct ReposNodesRefs {
-        pub nodes: Option<Vec<Option<ReposNodesRefsNodes>>>,
-    }
-    #[derive(Deserialize, Debug)]
-    pub struct ReposNodesRefsNodes {
-        pub target: Option<ReposNodesRefsNodesTarget>,
+    pub struct ReposNodesDefaultBranchRef {
+        pub target: Option<ReposNodesDefaultBranchRefTarget>,
     }
     #[derive(Deserialize, Debug)]
     #[serde(tag = "__typename")]
-    pub enum ReposNodesRefsNodesTarget {
+    pub enum ReposNodesDefaultBranchRefTarget {
         Blob,
-        Commit(ReposNodesRefsNodesTargetOnCommit),
+        Commit(ReposNodesDefaultBranchRefTargetOnCommit),
         Tag,
         Tree,
     }
     #[derive(Deserialize, Debug)]
-    pub struct ReposNodesRefsNodesTargetOnCommit {
-        #[serde(rename = "pushedDate")]
-        pub pushed_date: Option<DateTime>,
+    pub struct ReposNodesDefaultBranchRefTargetOnCommit {
+        pub history: ReposNodesDefaultBranchRefTargetOnCommitHistory,
+    }
+    #[derive(Deserialize, Debug)]
+    pub struct ReposNodesDefaultBranchRefTargetOnCommitHistory {
+        pub nodes: Option<Vec<Option<ReposNodesDefaultBranchRefTargetOnCommitHistoryNodes>>>,
+    }
+    #[derive(Deserialize, Debug)]
+    pub struct ReposNodesDefaultBranchRefTargetOnCommitHistoryNodes {
+        #[serde(rename = "committedDate")]
+        pub committed_date: DateTime,
+        pub author: Option<ReposNodesDefaultBranchRefTargetOnCommitHistoryNodesAuthor>,
+    }
+    #[derive(Deserialize, Debug)]
+    pub struct ReposNodesDefaultBranchRefTargetOnCommitHistoryNodesAuthor {
+        pub email: Option<String>,
+        pub name: Option<String>,
+        pub user: Option<ReposNodesDefaultBranchRefTargetOnCommitHistoryNodesAuthorUser>,
+    }
+    #[derive(Deserialize, Debug)]
+    pub struct ReposNodesDefaultBranchRefTargetOnCommitHistoryNodesAuthorUser {
+        pub login: String,
     }
     #[derive(Deserialize, Debug)]
     pub struct ResponseData {
@@ -148,7 +177,7 @@ pub mod user_repos_query {
     #![allow(dead_code)]
     use std::result::Result;
     pub const OPERATION_NAME: &str = "UserReposQuery";
-    pub const QUERY : & str = "fragment repos on RepositoryConnection {\n  pageInfo {\n    endCursor\n    hasNextPage\n  }\n  nodes {\n    createdAt\n    pushedAt\n    forkCount\n    isArchived\n    isDisabled\n    isEmpty\n    isFork\n    isMirror\n    isPrivate\n    nameWithOwner\n    languages(first: 100) {\n      edges {\n        size\n      }\n      nodes {\n        color\n        name\n      }\n      totalSize\n    }\n    licenseInfo {\n      nickname\n      spdxId\n      name\n    }\n    owner {\n      __typename\n      login\n    }\n    stargazerCount\n    url\n  }\n}\n\nquery OrganizationReposQuery($login: String!, $after: String) {\n  organization(login: $login) {\n    repositories(\n      affiliations: [OWNER],\n      after: $after,\n      orderBy: {\n        direction: ASC,\n        field: NAME,\n      },\n      privacy: PUBLIC,\n    ) {\n      ...repos\n    }\n  }\n}\n\nquery UserReposQuery($login: String!, $after: String) {\n  user(login: $login) {\n    createdAt\n    repositories(\n      affiliations: [OWNER],\n      after: $after,\n      orderBy: {\n        direction: ASC,\n        field: NAME,\n      },\n      privacy: PUBLIC,\n    ) {\n      ...repos\n    }\n  }\n}\n\n# Adapted from queries in\n# https://github.com/lowlighter/metrics/blob/master/source/plugins/followup/querie/s\nquery IssuesAndPrsQuery {\n  issues_created:search(query: \"author:AndreasOM is:issue\", type: ISSUE, first: 0) {\n    issueCount\n  }\n  issues_closed:search(query: \"author:AndreasOM is:issue is:closed\", type: ISSUE, first: 0) {\n    issueCount\n  }\n  prs_created:search(query: \"author:AndreasOM is:pr\", type: ISSUE, first: 0) {\n    issueCount\n  }\n  prs_merged:search(query: \"author:AndreasOM is:pr is:merged\", type: ISSUE, first: 0) {\n    issueCount\n  }\n}\n" ;
+    pub const QUERY : & str = "fragment repos on RepositoryConnection {\n  pageInfo {\n    endCursor\n    hasNextPage\n  }\n  nodes {\n    createdAt\n    pushedAt\n    defaultBranchRef {\n      target {\n        __typename\n        ... on Commit {\n          history(first: 50) {\n            nodes {\n              committedDate\n              author {\n                email\n                name\n                user {\n                  login\n                }\n              }\n            }\n          }\n        }\n      }\n    }\n    forkCount\n    isArchived\n    isDisabled\n    isEmpty\n    isFork\n    isMirror\n    isPrivate\n    nameWithOwner\n    description\n    homepageUrl\n    openGraphImageUrl\n    primaryLanguage {\n      color\n      name\n    }\n    languages(first: 100) {\n      edges {\n        size\n      }\n      nodes {\n        color\n        name\n      }\n      totalSize\n    }\n    licenseInfo {\n      nickname\n      spdxId\n      name\n    }\n    owner {\n      __typename\n      login\n    }\n    stargazerCount\n    url\n  }\n}\n\nquery OrganizationReposQuery($login: String!, $after: String) {\n  organization(login: $login) {\n    repositories(\n      affiliations: [OWNER],\n      after: $after,\n      orderBy: {\n        direction: ASC,\n        field: NAME,\n      },\n      privacy: PUBLIC,\n    ) {\n      ...repos\n    }\n  }\n}\n\nquery UserReposQuery($login: String!, $after: String) {\n  user(login: $login) {\n    createdAt\n    repositories(\n      affiliations: [OWNER],\n      after: $after,\n      orderBy: {\n        direction: ASC,\n        field: NAME,\n      },\n      privacy: PUBLIC,\n    ) {\n      ...repos\n    }\n  }\n}\n\n# Adapted from queries in\n# https://github.com/lowlighter/metrics/blob/master/source/plugins/followup/querie/s\nquery IssuesAndPrsQuery {\n  issues_created:search(query: \"author:AndreasOM is:issue\", type: ISSUE, first: 0) {\n    issueCount\n  }\n  issues_closed:search(query: \"author:AndreasOM is:issue is:closed\", type: ISSUE, first: 0) {\n    issueCount\n  }\n  prs_created:search(query: \"author:AndreasOM is:pr\", type: ISSUE, first: 0) {\n    issueCount\n  }\n  prs_merged:search(query: \"author:AndreasOM is:pr is:merged\", type: ISSUE, first: 0) {\n    issueCount\n  }\n}\n\nquery StargazersQuery($owner: String!, $name: String!, $after: String) {\n  repository(owner: $owner, name: $name) {\n    stargazers(\n      first: 100,\n      after: $after,\n      orderBy: {\n        direction: ASC,\n        field: STARRED_AT,\n      },\n    ) {\n      pageInfo {\n        endCursor\n        hasNextPage\n      }\n      edges {\n        starredAt\n      }\n    }\n  }\n}\n\nquery StarredReposQuery($login: String!, $after: String) {\n  user(login: $login) {\n    repositories(\n      affiliations: [OWNER],\n      after: $after,\n      orderBy: {\n        direction: ASC,\n        field: NAME,\n      },\n      privacy: PUBLIC,\n    ) {\n      pageInfo {\n        endCursor\n        hasNextPage\n      }\n      nodes {\n        isArchived\n        isDisabled\n        isEmpty\n        isFork\n        isMirror\n        isPrivate\n        nameWithOwner\n        owner {\n          login\n        }\n        stargazerCount\n        url\n      }\n    }\n  }\n}\n" ;
     use super::*;
     use serde::{Deserialize, Serialize};
     #[allow(dead_code)]
@@ -186,6 +215,8 @@ pub mod user_repos_query {
         pub created_at: DateTime,
         #[serde(rename = "pushedAt")]
         pub pushed_at: Option<DateTime>,
+        #[serde(rename = "defaultBranchRef")]
+        pub default_branch_ref: Option<ReposNodesDefaultBranchRef>,
         #[serde(rename = "forkCount")]
         pub fork_count: Int,
         #[serde(rename = "isArchived")]
@@ -202,6 +233,13 @@ pub mod user_repos_query {
         pub is_private: Boolean,
         #[serde(rename = "nameWithOwner")]
         pub name_with_owner: String,
+        pub description: Option<String>,
+        #[serde(rename = "homepageUrl")]
+        pub homepage_url: Option<URI>,
+        #[serde(rename = "openGraphImageUrl")]
+        pub open_graph_image_url: URI,
+        #[serde(rename = "primaryLanguage")]
+        pub primary_language: Option<ReposNodesPrimaryLanguage>,
         pub languages: Option<ReposNodesLanguages>,
         #[serde(rename = "licenseInfo")]
         pub license_info: Option<ReposNodesLicenseInfo>,
@@ -211,6 +249,11 @@ pub mod user_repos_query {
         pub url: URI,
     }
     #[derive(Deserialize, Debug)]
+    pub struct ReposNodesPrimaryLanguage {
+        pub color: Option<String>,
+        pub name: String,
+    }
+    #[derive(Deserialize, Debug)]
     pub struct ReposNodesLanguages {
         pub edges: Option<Vec<Option<ReposNodesLanguagesEdges>>>,
         pub nodes: Option<Vec<Option<ReposNodesLanguagesNodes>>>,
@@ -246,25 +289,40 @@ pub mod user_repos_query {
         User,
     }
     #[derive(Deserialize, Debug)]
-    pub struct ReposNodesRefs {
-        pub nodes: Option<Vec<Option<ReposNodesRefsNodes>>>,
-    }
-    #[derive(Deserialize, Debug)]
-    pub struct ReposNodesRefsNodes {
-        pub target: Option<ReposNodesRefsNodesTarget>,
+    pub struct ReposNodesDefaultBranchRef {
+        pub target: Option<ReposNodesDefaultBranchRefTarget>,
     }
     #[derive(Deserialize, Debug)]
     #[serde(tag = "__typename")]
-    pub enum ReposNodesRefsNodesTarget {
+    pub enum ReposNodesDefaultBranchRefTarget {
         Blob,
-        Commit(ReposNodesRefsNodesTargetOnCommit),
+        Commit(ReposNodesDefaultBranchRefTargetOnCommit),
         Tag,
         Tree,
     }
     #[derive(Deserialize, Debug)]
-    pub struct ReposNodesRefsNodesTargetOnCommit {
-        #[serde(rename = "pushedDate")]
-        pub pushed_date: Option<DateTime>,
+    pub struct ReposNodesDefaultBranchRefTargetOnCommit {
+        pub history: ReposNodesDefaultBranchRefTargetOnCommitHistory,
+    }
+    #[derive(Deserialize, Debug)]
+    pub struct ReposNodesDefaultBranchRefTargetOnCommitHistory {
+        pub nodes: Option<Vec<Option<ReposNodesDefaultBranchRefTargetOnCommitHistoryNodes>>>,
+    }
+    #[derive(Deserialize, Debug)]
+    pub struct ReposNodesDefaultBranchRefTargetOnCommitHistoryNodes {
+        #[serde(rename = "committedDate")]
+        pub committed_date: DateTime,
+        pub author: Option<ReposNodesDefaultBranchRefTargetOnCommitHistoryNodesAuthor>,
+    }
+    #[derive(Deserialize, Debug)]
+    pub struct ReposNodesDefaultBranchRefTargetOnCommitHistoryNodesAuthor {
+        pub email: Option<String>,
+        pub name: Option<String>,
+        pub user: Option<ReposNodesDefaultBranchRefTargetOnCommitHistoryNodesAuthorUser>,
+    }
+    #[derive(Deserialize, Debug)]
+    pub struct ReposNodesDefaultBranchRefTargetOnCommitHistoryNodesAuthorUser {
+        pub login: String,
     }
     #[derive(Deserialize, Debug)]
     pub struct ResponseData {
@@ -294,7 +352,7 @@ pub mod issues_and_prs_query {
     #![allow(dead_code)]
     use std::result::Result;
     pub const OPERATION_NAME: &str = "IssuesAndPrsQuery";
-    pub const QUERY : & str = "fragment repos on RepositoryConnection {\n  pageInfo {\n    endCursor\n    hasNextPage\n  }\n  nodes {\n    createdAt\n    pushedAt\n    forkCount\n    isArchived\n    isDisabled\n    isEmpty\n    isFork\n    isMirror\n    isPrivate\n    nameWithOwner\n    languages(first: 100) {\n      edges {\n        size\n      }\n      nodes {\n        color\n        name\n      }\n      totalSize\n    }\n    licenseInfo {\n      nickname\n      spdxId\n      name\n    }\n    owner {\n      __typename\n      login\n    }\n    stargazerCount\n    url\n  }\n}\n\nquery OrganizationReposQuery($login: String!, $after: String) {\n  organization(login: $login) {\n    repositories(\n      affiliations: [OWNER],\n      after: $after,\n      orderBy: {\n        direction: ASC,\n        field: NAME,\n      },\n      privacy: PUBLIC,\n    ) {\n      ...repos\n    }\n  }\n}\n\nquery UserReposQuery($login: String!, $after: String) {\n  user(login: $login) {\n    createdAt\n    repositories(\n      affiliations: [OWNER],\n      after: $after,\n      orderBy: {\n        direction: ASC,\n        field: NAME,\n      },\n      privacy: PUBLIC,\n    ) {\n      ...repos\n    }\n  }\n}\n\n# Adapted from queries in\n# https://github.com/lowlighter/metrics/blob/master/source/plugins/followup/querie/s\nquery IssuesAndPrsQuery {\n  issues_created:search(query: \"author:AndreasOM is:issue\", type: ISSUE, first: 0) {\n    issueCount\n  }\n  issues_closed:search(query: \"author:AndreasOM is:issue is:closed\", type: ISSUE, first: 0) {\n    issueCount\n  }\n  prs_created:search(query: \"author:AndreasOM is:pr\", type: ISSUE, first: 0) {\n    issueCount\n  }\n  prs_merged:search(query: \"author:AndreasOM is:pr is:merged\", type: ISSUE, first: 0) {\n    issueCount\n  }\n}\n" ;
+    pub const QUERY : & str = "fragment repos on RepositoryConnection {\n  pageInfo {\n    endCursor\n    hasNextPage\n  }\n  nodes {\n    createdAt\n    pushedAt\n    defaultBranchRef {\n      target {\n        __typename\n        ... on Commit {\n          history(first: 50) {\n            nodes {\n              committedDate\n              author {\n                email\n                name\n                user {\n                  login\n                }\n              }\n            }\n          }\n        }\n      }\n    }\n    forkCount\n    isArchived\n    isDisabled\n    isEmpty\n    isFork\n    isMirror\n    isPrivate\n    nameWithOwner\n    description\n    homepageUrl\n    openGraphImageUrl\n    primaryLanguage {\n      color\n      name\n    }\n    languages(first: 100) {\n      edges {\n        size\n      }\n      nodes {\n        color\n        name\n      }\n      totalSize\n    }\n    licenseInfo {\n      nickname\n      spdxId\n      name\n    }\n    owner {\n      __typename\n      login\n    }\n    stargazerCount\n    url\n  }\n}\n\nquery OrganizationReposQuery($login: String!, $after: String) {\n  organization(login: $login) {\n    repositories(\n      affiliations: [OWNER],\n      after: $after,\n      orderBy: {\n        direction: ASC,\n        field: NAME,\n      },\n      privacy: PUBLIC,\n    ) {\n      ...repos\n    }\n  }\n}\n\nquery UserReposQuery($login: String!, $after: String) {\n  user(login: $login) {\n    createdAt\n    repositories(\n      affiliations: [OWNER],\n      after: $after,\n      orderBy: {\n        direction: ASC,\n        field: NAME,\n      },\n      privacy: PUBLIC,\n    ) {\n      ...repos\n    }\n  }\n}\n\n# Adapted from queries in\n# https://github.com/lowlighter/metrics/blob/master/source/plugins/followup/querie/s\nquery IssuesAndPrsQuery {\n  issues_created:search(query: \"author:AndreasOM is:issue\", type: ISSUE, first: 0) {\n    issueCount\n  }\n  issues_closed:search(query: \"author:AndreasOM is:issue is:closed\", type: ISSUE, first: 0) {\n    issueCount\n  }\n  prs_created:search(query: \"author:AndreasOM is:pr\", type: ISSUE, first: 0) {\n    issueCount\n  }\n  prs_merged:search(query: \"author:AndreasOM is:pr is:merged\", type: ISSUE, first: 0) {\n    issueCount\n  }\n}\n\nquery StargazersQuery($owner: String!, $name: String!, $after: String) {\n  repository(owner: $owner, name: $name) {\n    stargazers(\n      first: 100,\n      after: $after,\n      orderBy: {\n        direction: ASC,\n        field: STARRED_AT,\n      },\n    ) {\n      pageInfo {\n        endCursor\n        hasNextPage\n      }\n      edges {\n        starredAt\n      }\n    }\n  }\n}\n\nquery StarredReposQuery($login: String!, $after: String) {\n  user(login: $login) {\n    repositories(\n      affiliations: [OWNER],\n      after: $after,\n      orderBy: {\n        direction: ASC,\n        field: NAME,\n      },\n      privacy: PUBLIC,\n    ) {\n      pageInfo {\n        endCursor\n        hasNextPage\n      }\n      nodes {\n        isArchived\n        isDisabled\n        isEmpty\n        isFork\n        isMirror\n        isPrivate\n        nameWithOwner\n        owner {\n          login\n        }\n        stargazerCount\n        url\n      }\n    }\n  }\n}\n" ;
     use super::*;
     use serde::{Deserialize, Serialize};
     #[allow(dead_code)]
@@ -346,3 +404,146 @@ impl graphql_client::GraphQLQuery for IssuesAndPrsQuery {
         }
     }
 }
+pub struct StargazersQuery;
+pub mod stargazers_query {
+    #![allow(dead_code)]
+    use std::result::Result;
+    pub const OPERATION_NAME: &str = "StargazersQuery";
+    pub const QUERY : & str = "fragment repos on RepositoryConnection {\n  pageInfo {\n    endCursor\n    hasNextPage\n  }\n  nodes {\n    createdAt\n    pushedAt\n    defaultBranchRef {\n      target {\n        __typename\n        ... on Commit {\n          history(first: 50) {\n            nodes {\n              committedDate\n              author {\n                email\n                name\n                user {\n                  login\n                }\n              }\n            }\n          }\n        }\n      }\n    }\n    forkCount\n    isArchived\n    isDisabled\n    isEmpty\n    isFork\n    isMirror\n    isPrivate\n    nameWithOwner\n    description\n    homepageUrl\n    openGraphImageUrl\n    primaryLanguage {\n      color\n      name\n    }\n    languages(first: 100) {\n      edges {\n        size\n      }\n      nodes {\n        color\n        name\n      }\n      totalSize\n    }\n    licenseInfo {\n      nickname\n      spdxId\n      name\n    }\n    owner {\n      __typename\n      login\n    }\n    stargazerCount\n    url\n  }\n}\n\nquery OrganizationReposQuery($login: String!, $after: String) {\n  organization(login: $login) {\n    repositories(\n      affiliations: [OWNER],\n      after: $after,\n      orderBy: {\n        direction: ASC,\n        field: NAME,\n      },\n      privacy: PUBLIC,\n    ) {\n      ...repos\n    }\n  }\n}\n\nquery UserReposQuery($login: String!, $after: String) {\n  user(login: $login) {\n    createdAt\n    repositories(\n      affiliations: [OWNER],\n      after: $after,\n      orderBy: {\n        direction: ASC,\n        field: NAME,\n      },\n      privacy: PUBLIC,\n    ) {\n      ...repos\n    }\n  }\n}\n\n# Adapted from queries in\n# https://github.com/lowlighter/metrics/blob/master/source/plugins/followup/querie/s\nquery IssuesAndPrsQuery {\n  issues_created:search(query: \"author:AndreasOM is:issue\", type: ISSUE, first: 0) {\n    issueCount\n  }\n  issues_closed:search(query: \"author:AndreasOM is:issue is:closed\", type: ISSUE, first: 0) {\n    issueCount\n  }\n  prs_created:search(query: \"author:AndreasOM is:pr\", type: ISSUE, first: 0) {\n    issueCount\n  }\n  prs_merged:search(query: \"author:AndreasOM is:pr is:merged\", type: ISSUE, first: 0) {\n    issueCount\n  }\n}\n\nquery StargazersQuery($owner: String!, $name: String!, $after: String) {\n  repository(owner: $owner, name: $name) {\n    stargazers(\n      first: 100,\n      after: $after,\n      orderBy: {\n        direction: ASC,\n        field: STARRED_AT,\n      },\n    ) {\n      pageInfo {\n        endCursor\n        hasNextPage\n      }\n      edges {\n        starredAt\n      }\n    }\n  }\n}\n\nquery StarredReposQuery($login: String!, $after: String) {\n  user(login: $login) {\n    repositories(\n      affiliations: [OWNER],\n      after: $after,\n      orderBy: {\n        direction: ASC,\n        field: NAME,\n      },\n      privacy: PUBLIC,\n    ) {\n      pageInfo {\n        endCursor\n        hasNextPage\n      }\n      nodes {\n        isArchived\n        isDisabled\n        isEmpty\n        isFork\n        isMirror\n        isPrivate\n        nameWithOwner\n        owner {\n          login\n        }\n        stargazerCount\n        url\n      }\n    }\n  }\n}\n" ;
+    use super::*;
+    use serde::{Deserialize, Serialize};
+    #[allow(dead_code)]
+    type Boolean = bool;
+    #[allow(dead_code)]
+    type Float = f64;
+    #[allow(dead_code)]
+    type Int = i64;
+    #[allow(dead_code)]
+    type ID = String;
+    type DateTime = crate::gql_types::DateTime;
+    #[derive(Serialize, Clone)]
+    pub struct Variables {
+        pub owner: String,
+        pub name: String,
+        pub after: Option<String>,
+    }
+    impl Variables {}
+    #[derive(Deserialize, Debug)]
+    pub struct ResponseData {
+        pub repository: Option<StargazersQueryRepository>,
+    }
+    #[derive(Deserialize, Debug)]
+    pub struct StargazersQueryRepository {
+        pub stargazers: StargazersQueryRepositoryStargazers,
+    }
+    #[derive(Deserialize, Debug)]
+    pub struct StargazersQueryRepositoryStargazers {
+        #[serde(rename = "pageInfo")]
+        pub page_info: StargazersQueryRepositoryStargazersPageInfo,
+        pub edges: Option<Vec<Option<StargazersQueryRepositoryStargazersEdges>>>,
+    }
+    #[derive(Deserialize, Debug)]
+    pub struct StargazersQueryRepositoryStargazersPageInfo {
+        #[serde(rename = "endCursor")]
+        pub end_cursor: Option<String>,
+        #[serde(rename = "hasNextPage")]
+        pub has_next_page: Boolean,
+    }
+    #[derive(Deserialize, Debug)]
+    pub struct StargazersQueryRepositoryStargazersEdges {
+        #[serde(rename = "starredAt")]
+        pub starred_at: DateTime,
+    }
+}
+impl graphql_client::GraphQLQuery for StargazersQuery {
+    type Variables = stargazers_query::Variables;
+    type ResponseData = stargazers_query::ResponseData;
+    fn build_query(variables: Self::Variables) -> ::graphql_client::QueryBody<Self::Variables> {
+        graphql_client::QueryBody {
+            variables,
+            query: stargazers_query::QUERY,
+            operation_name: stargazers_query::OPERATION_NAME,
+        }
+    }
+}
+pub struct StarredReposQuery;
+pub mod starred_repos_query {
+    #![allow(dead_code)]
+    use std::result::Result;
+    pub const OPERATION_NAME: &str = "StarredReposQuery";
+    pub const QUERY : & str = "fragment repos on RepositoryConnection {\n  pageInfo {\n    endCursor\n    hasNextPage\n  }\n  nodes {\n    createdAt\n    pushedAt\n    defaultBranchRef {\n      target {\n        __typename\n        ... on Commit {\n          history(first: 50) {\n            nodes {\n              committedDate\n              author {\n                email\n                name\n                user {\n                  login\n                }\n              }\n            }\n          }\n        }\n      }\n    }\n    forkCount\n    isArchived\n    isDisabled\n    isEmpty\n    isFork\n    isMirror\n    isPrivate\n    nameWithOwner\n    description\n    homepageUrl\n    openGraphImageUrl\n    primaryLanguage {\n      color\n      name\n    }\n    languages(first: 100) {\n      edges {\n        size\n      }\n      nodes {\n        color\n        name\n      }\n      totalSize\n    }\n    licenseInfo {\n      nickname\n      spdxId\n      name\n    }\n    owner {\n      __typename\n      login\n    }\n    stargazerCount\n    url\n  }\n}\n\nquery OrganizationReposQuery($login: String!, $after: String) {\n  organization(login: $login) {\n    repositories(\n      affiliations: [OWNER],\n      after: $after,\n      orderBy: {\n        direction: ASC,\n        field: NAME,\n      },\n      privacy: PUBLIC,\n    ) {\n      ...repos\n    }\n  }\n}\n\nquery UserReposQuery($login: String!, $after: String) {\n  user(login: $login) {\n    createdAt\n    repositories(\n      affiliations: [OWNER],\n      after: $after,\n      orderBy: {\n        direction: ASC,\n        field: NAME,\n      },\n      privacy: PUBLIC,\n    ) {\n      ...repos\n    }\n  }\n}\n\n# Adapted from queries in\n# https://github.com/lowlighter/metrics/blob/master/source/plugins/followup/querie/s\nquery IssuesAndPrsQuery {\n  issues_created:search(query: \"author:AndreasOM is:issue\", type: ISSUE, first: 0) {\n    issueCount\n  }\n  issues_closed:search(query: \"author:AndreasOM is:issue is:closed\", type: ISSUE, first: 0) {\n    issueCount\n  }\n  prs_created:search(query: \"author:AndreasOM is:pr\", type: ISSUE, first: 0) {\n    issueCount\n  }\n  prs_merged:search(query: \"author:AndreasOM is:pr is:merged\", type: ISSUE, first: 0) {\n    issueCount\n  }\n}\n\nquery StargazersQuery($owner: String!, $name: String!, $after: String) {\n  repository(owner: $owner, name: $name) {\n    stargazers(\n      first: 100,\n      after: $after,\n      orderBy: {\n        direction: ASC,\n        field: STARRED_AT,\n      },\n    ) {\n      pageInfo {\n        endCursor\n        hasNextPage\n      }\n      edges {\n        starredAt\n      }\n    }\n  }\n}\n\nquery StarredReposQuery($login: String!, $after: String) {\n  user(login: $login) {\n    repositories(\n      affiliations: [OWNER],\n      after: $after,\n      orderBy: {\n        direction: ASC,\n        field: NAME,\n      },\n      privacy: PUBLIC,\n    ) {\n      pageInfo {\n        endCursor\n        hasNextPage\n      }\n      nodes {\n        isArchived\n        isDisabled\n        isEmpty\n        isFork\n        isMirror\n        isPrivate\n        nameWithOwner\n        owner {\n          login\n        }\n        stargazerCount\n        url\n      }\n    }\n  }\n}\n" ;
+    use super::*;
+    use serde::{Deserialize, Serialize};
+    #[allow(dead_code)]
+    type Boolean = bool;
+    #[allow(dead_code)]
+    type Float = f64;
+    #[allow(dead_code)]
+    type Int = i64;
+    #[allow(dead_code)]
+    type ID = String;
+    type URI = crate::gql_types::URI;
+    #[derive(Serialize, Clone)]
+    pub struct Variables {
+        pub login: String,
+        pub after: Option<String>,
+    }
+    impl Variables {}
+    #[derive(Deserialize, Debug)]
+    pub struct ResponseData {
+        pub user: Option<StarredReposQueryUser>,
+    }
+    #[derive(Deserialize, Debug)]
+    pub struct StarredReposQueryUser {
+        pub repositories: StarredReposQueryUserRepositories,
+    }
+    #[derive(Deserialize, Debug)]
+    pub struct StarredReposQueryUserRepositories {
+        #[serde(rename = "pageInfo")]
+        pub page_info: StarredReposQueryUserRepositoriesPageInfo,
+        pub nodes: Option<Vec<Option<StarredReposQueryUserRepositoriesNodes>>>,
+    }
+    #[derive(Deserialize, Debug)]
+    pub struct StarredReposQueryUserRepositoriesPageInfo {
+        #[serde(rename = "endCursor")]
+        pub end_cursor: Option<String>,
+        #[serde(rename = "hasNextPage")]
+        pub has_next_page: Boolean,
+    }
+    #[derive(Deserialize, Debug)]
+    pub struct StarredReposQueryUserRepositoriesNodes {
+        #[serde(rename = "isArchived")]
+        pub is_archived: Boolean,
+        #[serde(rename = "isDisabled")]
+        pub is_disabled: Boolean,
+        #[serde(rename = "isEmpty")]
+        pub is_empty: Boolean,
+        #[serde(rename = "isFork")]
+        pub is_fork: Boolean,
+        #[serde(rename = "isMirror")]
+        pub is_mirror: Boolean,
+        #[serde(rename = "isPrivate")]
+        pub is_private: Boolean,
+        #[serde(rename = "nameWithOwner")]
+        pub name_with_owner: String,
+        pub owner: StarredReposQueryUserRepositoriesNodesOwner,
+        #[serde(rename = "stargazerCount")]
+        pub stargazer_count: Int,
+        pub url: URI,
+    }
+    #[derive(Deserialize, Debug)]
+    pub struct StarredReposQueryUserRepositoriesNodesOwner {
+        pub login: String,
+    }
+}
+impl graphql_client::GraphQLQuery for StarredReposQuery {
+    type Variables = starred_repos_query::Variables;
+    type ResponseData = starred_repos_query::ResponseData;
+    fn build_query(variables: Self::Variables) -> ::graphql_client::QueryBody<Self::Variables> {
+        graphql_client::QueryBody {
+            variables,
+            query: starred_repos_query::QUERY,
+            operation_name: starred_repos_query::OPERATION_NAME,
+        }
+    }
+}
@@ -0,0 +1,140 @@
+// A standalone HTML profile page rendered from the same `Context` as the
+// README. CSS, JS and the SVG charts are all inlined, so the page can be
+// served from any static host as a single file.
+use anyhow::Result;
+use serde::Serialize;
+use tinytemplate::TinyTemplate;
+
+use crate::{svg, Context};
+
+const PROFILE_TEMPLATE: &str = include_str!("../html/profile.html");
+const PROFILE_STYLE: &str = include_str!("../html/profile.css");
+const PROFILE_SCRIPT: &str = include_str!("../html/profile.js");
+
+const MERGED_COLOR: &str = "#8250df";
+const CLOSED_COLOR: &str = "#2da44e";
+
+#[derive(Serialize)]
+struct Charts {
+    recent_languages: Option<String>,
+    all_time_languages: Option<String>,
+    prs_merged: Option<String>,
+    issues_closed: Option<String>,
+    star_history: Option<String>,
+    language_evolution: Option<String>,
+}
+
+#[derive(Serialize)]
+struct HtmlContext<'a> {
+    #[serde(flatten)]
+    context: &'a Context<'a>,
+    login: &'a str,
+    style: &'static str,
+    script: &'static str,
+    charts: Charts,
+}
+
+pub fn render(context: &Context<'_>, login: &str) -> Result<String> {
+    let charts = Charts {
+        recent_languages: context.top_recent_languages.as_deref().map(svg::language_bar),
+        all_time_languages: context.top_all_time_languages.as_deref().map(svg::language_bar),
+        prs_merged: context.issue_and_pr_stats.map(|s| {
+            let label = format!("{} of {} PRs merged", s.prs_merged, s.prs_created);
+            svg::ratio_bar(s.prs_merged, s.prs_created, MERGED_COLOR, &label)
+        }),
+        issues_closed: context.issue_and_pr_stats.map(|s| {
+            let label = format!("{} of {} issues closed", s.issues_closed, s.issues_created);
+            svg::ratio_bar(s.issues_closed, s.issues_created, CLOSED_COLOR, &label)
+        }),
+        star_history: context.star_history.as_ref().map(|g| svg::star_history(g, context.now.naive_utc().date())),
+        language_evolution: context
+            .language_evolution
+            .as_deref()
+            .filter(|y| !y.is_empty())
+            .map(svg::language_evolution),
+    };
+
+    let mut tt = TinyTemplate::new();
+    tt.add_formatter("unescaped", tinytemplate::format_unescaped);
+    tt.add_template("profile", PROFILE_TEMPLATE)?;
+    Ok(tt.render(
+        "profile",
+        &HtmlContext {
+            context,
+            login,
+            style: PROFILE_STYLE,
+            script: PROFILE_SCRIPT,
+            charts,
+        },
+    )?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{sample, LanguageGroupTotals, SourceStatus, Sources};
+
+    #[test]
+    fn renders_every_section() {
+        let mut stats = sample::user_and_repo_stats();
+        stats.repos[0].description = Some("Games & <tools>".to_string());
+        let groups = LanguageGroupTotals::new(&stats);
+        let (activity, stars) = (sample::issue_and_pr_stats(), sample::star_history());
+        let context = Context::new(
+            Some(&stats),
+            Some(&groups),
+            &[],
+            Some(&activity),
+            Some(&stars),
+            Sources::default(),
+            sample::now(),
+        );
+        let html = render(&context, "Andreas\"OM\"").unwrap();
+        for heading in ["Repo Stats", "Active Repos", "Past Two Years Language Stats", "All-Time Language Stats", "Activity", "Star History"] {
+            assert!(html.contains(&format!("<h2>{heading}</h2>")), "no {heading} in {html}");
+        }
+        assert!(html.contains(&format!("<strong>{}</strong> repos with commits", stats.live_repos)));
+        assert!(html.contains("<svg"));
+        assert!(html.contains("Games &amp; &lt;tools&gt;"), "{html}");
+        assert!(!html.contains("<tools>"));
+        assert!(html.contains("<title>Andreas&quot;OM&quot;</title>"), "{html}");
+        assert!(!html.contains("class=\"note\""));
+    }
+
+    #[test]
+    fn notes_unavailable_and_snapshotted_sources() {
+        let unavailable = || SourceStatus {
+            snapshot_date: None,
+            unavailable: true,
+        };
+        let sources = Sources {
+            user_and_repo_stats: unavailable(),
+            issue_and_pr_stats: unavailable(),
+            star_history: unavailable(),
+        };
+        let html = render(&Context::new(None, None, &[], None, None, sources, sample::now()), "AndreasOM").unwrap();
+        assert!(html.contains("Repo stats are currently unavailable."), "{html}");
+        assert!(html.contains("Issue and PR stats are currently unavailable."), "{html}");
+        assert!(html.contains("The star history is currently unavailable."), "{html}");
+        assert!(!html.contains("<h2>Repo Stats</h2>"));
+        assert!(!html.contains("<h2>Star History</h2>"));
+
+        let snapshotted = || SourceStatus {
+            snapshot_date: Some("2024-06-01".to_string()),
+            unavailable: false,
+        };
+        let sources = Sources {
+            user_and_repo_stats: snapshotted(),
+            issue_and_pr_stats: snapshotted(),
+            star_history: snapshotted(),
+        };
+        let stats = sample::user_and_repo_stats();
+        let (activity, stars) = (sample::issue_and_pr_stats(), sample::star_history());
+        let context = Context::new(Some(&stats), None, &[], Some(&activity), Some(&stars), sources, sample::now());
+        let html = render(&context, "AndreasOM").unwrap();
+        let fallback = "These stats are from 2024-06-01, because fetching fresh data failed.";
+        assert_eq!(html.matches(fallback).count(), 2, "{html}");
+        assert!(html.contains("The star history is from 2024-06-01, because fetching fresh data failed."), "{html}");
+        assert!(!html.contains("currently unavailable"));
+    }
+}
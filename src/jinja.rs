@@ -0,0 +1,136 @@
+// Renders `.jinja` templates with minijinja, for templates that need more than
+// TinyTemplate offers: filters, arithmetic, and inheritance. A template gets
+// the same context as the TinyTemplate README, so ordering and limits can
+// move into the template:
+//
+//     {% extends "base.md.jinja" %}
+//     {% block body %}
+//     {% for repo in user_and_repo_stats.repos | sort(attribute="stargazer_count", reverse=true) %}
+//     {%- if loop.index <= 3 %}- [{{ repo.full_name }}]({{ repo.url | url }}) {{ repo.pushed_date | date("%b %Y") }}
+//     {% endif %}{% endfor %}
+//     {% endblock %}
+//
+// Other templates are loaded from the directory of the rendered one. Besides
+// minijinja's built-in filters (`sort`, `slice`, `groupby`, `batch`, ...)
+// there are `date(format)` and the named formatters of TinyTemplate
+// templates, see `formatters`. As there, values are Markdown-escaped, unless
+// they are marked `| safe`, like the pre-rendered `mermaid.*` charts.
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use minijinja::{path_loader, AutoEscape, Environment, Error, ErrorKind, State, Value};
+use serde::Serialize;
+use std::path::Path;
+
+use crate::formatters;
+
+/// Renders the template at `path`. Relative dates are relative to `now`.
+pub fn render(path: &Path, context: &impl Serialize, now: DateTime<Utc>) -> Result<String> {
+    let name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| anyhow::anyhow!("Not a template file: {}", path.display()))?;
+    let dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let env = environment(dir, now);
+    Ok(env.get_template(name)?.render(context)?)
+}
+
+fn environment(dir: &Path, now: DateTime<Utc>) -> Environment<'static> {
+    let mut env = Environment::new();
+    env.set_loader(path_loader(dir));
+    env.set_formatter(markdown);
+    env.add_global("now", now.to_rfc3339());
+    env.add_filter("date", date);
+    env.add_filter("compact", filter(formatters::compact));
+    env.add_filter("relative_date", filter(move |value, output| formatters::relative_date(value, output, now)));
+    env.add_filter("percent_bar", filter(formatters::percent_bar));
+    env.add_filter("plural", filter(formatters::plural));
+    env.add_filter("table_cell", filter(formatters::table_cell));
+    env.add_filter("url", filter(formatters::url));
+    env
+}
+
+// Escapes strings for Markdown, unless they are safe or the template is
+// auto-escaped already, e.g. as HTML.
+fn markdown(out: &mut minijinja::Output, state: &State, value: &Value) -> Result<(), Error> {
+    match value.as_str() {
+        Some(s) if !value.is_safe() && state.auto_escape() == AutoEscape::None => {
+            let mut escaped = String::with_capacity(s.len());
+            formatters::escape(s, &mut escaped);
+            out.write_str(&escaped).map_err(Error::from)
+        }
+        _ => minijinja::escape_formatter(out, state, value),
+    }
+}
+
+/// Formats a date or RFC 3339 time with a `strftime` format, e.g.
+/// `{{ repo.pushed_date | date("%b %Y") }}`.
+fn date(value: &str, format: Option<&str>) -> Result<String, Error> {
+    let date = formatters::parse_date(value)
+        .ok_or_else(|| Error::new(ErrorKind::InvalidOperation, format!("`{value}` is not a date")))?;
+    Ok(date.format(format.unwrap_or("%Y-%m-%d")).to_string())
+}
+
+// Wraps a TinyTemplate formatter as a filter. What it writes is final, so it
+// isn't escaped again.
+fn filter<F>(formatter: F) -> impl Fn(Value) -> Result<Value, Error> + Send + Sync + 'static
+where
+    F: Fn(&serde_json::Value, &mut String) -> tinytemplate::error::Result<()> + Send + Sync + 'static,
+{
+    move |value| {
+        let error = |e: &dyn std::fmt::Display| Error::new(ErrorKind::InvalidOperation, e.to_string());
+        let value = serde_json::to_value(&value).map_err(|e| error(&e))?;
+        let mut output = String::new();
+        formatter(&value, &mut output).map_err(|e| error(&e))?;
+        Ok(Value::from_safe_string(output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn inheritance_and_filters() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("base.md.jinja"),
+            "# {{ title }}\n{% block body %}{% endblock %}\n_Updated {{ now | date(\"%Y\") }}_\n",
+        )
+        .unwrap();
+        let readme = dir.path().join("README.md.jinja");
+        std::fs::write(
+            &readme,
+            r#"{% extends "base.md.jinja" %}{% block body %}
+{%- for repo in repos | sort(attribute="stars", reverse=true) %}{% if loop.index <= 2 -%}
+- [{{ repo.name }}]({{ repo.url | url }}) {{ repo.stars | compact }} star{{ repo.stars | plural }}, {{ repo.pushed | date("%b %Y") }}
+{% endif %}{% endfor %}
+{%- for group in repos | groupby("language") %}{{ group.grouper }}: {{ group.list | length }}{% if not loop.last %}, {% endif %}{% endfor %}
+{%- endblock %}"#,
+        )
+        .unwrap();
+        let context = json!({
+            "title": "My *repos*",
+            "repos": [
+                {"name": "tiny_tool", "url": "https://example.com/t", "stars": 1, "pushed": "2024-01-05", "language": "Rust"},
+                {"name": "big_game", "url": "https://example.com/g (1)", "stars": 1234, "pushed": "2024-06-01T10:00:00Z", "language": "Rust"},
+                {"name": "old", "url": "https://example.com/o", "stars": 0, "pushed": "2019-01-01", "language": "C++"},
+            ],
+        });
+        let now = DateTime::parse_from_rfc3339("2024-06-15T00:00:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(
+            render(&readme, &context, now).unwrap(),
+            r"# My \*repos\*
+- [big\_game](https://example.com/g%20%281%29) 1.2k stars, Jun 2024
+- [tiny\_tool](https://example.com/t) 1 star, Jan 2024
+C++: 1, Rust: 2
+_Updated 2024_"
+        );
+    }
+
+    #[test]
+    fn bad_dates_are_errors() {
+        assert!(date("last week", None).is_err());
+        assert_eq!(date("2024-06-01T10:00:00Z", None).unwrap(), "2024-06-01");
+    }
+}
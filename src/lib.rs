@@ -0,0 +1,812 @@
+//! The stats engine behind the profile README: fetches repo, language and
+//! issue/PR data from the configured forges, aggregates it, and renders it
+//! into the README (or the HTML page in [`html`]).
+//!
+//! ```no_run
+//! # async fn run() -> anyhow::Result<()> {
+//! let client = generator::Client::builder("my-dashboard/1.0")
+//!     .github("AndreasOM", &std::env::var("GITHUB_TOKEN")?)?
+//!     .build();
+//! let now = chrono::Utc::now();
+//! let (repos, activity) = tokio::join!(client.user_and_repo_stats(now), client.issue_and_pr_stats());
+//! let (repos, activity) = (repos?, activity?);
+//! let cutoff = generator::aggregate::LANGUAGE_CUTOFF;
+//! let languages = generator::aggregate::top_languages(&repos.recent_languages, cutoff);
+//! # Ok(())
+//! # }
+//! ```
+
+// github_queries.rs is generated from graphql/github_queries.graphql against
+// GitHub's public schema (https://docs.github.com/en/graphql/overview/public-schema),
+// saved as graphql/github.schema.graphql but not committed:
+// graphql-client generate --schema-path ./graphql/github.schema.graphql --custom-scalars-module crate::gql_types --output-directory ./src/ --response-derives Debug ./graphql/github_queries.graphql
+pub mod aggregate;
+pub mod check;
+pub mod commit;
+pub mod forge;
+pub mod formatters;
+mod github_queries;
+pub mod html;
+#[cfg(feature = "jinja")]
+pub mod jinja;
+pub mod linguist;
+pub mod local_languages;
+pub mod mermaid;
+pub mod output;
+pub mod sample;
+pub mod schema;
+pub mod snapshot;
+pub mod svg;
+pub mod weighting;
+pub(crate) mod gql_types {
+    #[allow(clippy::upper_case_acronyms)]
+    pub(crate) type URI = String;
+    pub(crate) type DateTime = String; //chrono::DateTime<chrono::Utc>;
+}
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use forge::{Forge, GitHubForge};
+use futures::future::join_all;
+use local_languages::Counting;
+use regex::RegexSet;
+use serde::{Deserialize, Serialize};
+use snapshot::Snapshotted;
+use std::{
+    collections::{BTreeMap, HashMap},
+    future::Future,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+use tinytemplate::TinyTemplate;
+use weighting::Weighting;
+
+/// The template the README is rendered from.
+pub const README_TEMPLATE: &str = include_str!("../README_TEMPLATE.md");
+
+/// A live repo as listed in the README.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MyRepo {
+    pub full_name: String,
+    pub url: String,
+    pub fork_count: i64,
+    pub stargazer_count: i64,
+    pub pushed_date: String,
+    // YYYY-MM-DD, or empty when the forge didn't say, and the age in full
+    // years as of when the stats were collected.
+    #[serde(default)]
+    pub created_date: String,
+    #[serde(default)]
+    pub age_years: i64,
+    // On one line and at most `aggregate::DESCRIPTION_MAX_CHARS` long.
+    pub description: Option<String>,
+    pub homepage_url: Option<String>,
+    pub primary_language: Option<PrimaryLanguage>,
+    pub open_graph_image_url: Option<String>,
+}
+
+/// The main language of a repo, in the color of the language stats.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrimaryLanguage {
+    pub name: String,
+    pub color: String,
+}
+
+/// Account and repo totals, plus the language totals of all repos and of the
+/// recently pushed ones, keyed by language name.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserAndRepoStats {
+    pub created_at: String,
+    pub total_repos: i64,
+    pub owned_repos: i64,
+    // Stars and forks of all owned repos, not just the live ones.
+    pub total_stars: i64,
+    pub total_forks: i64,
+    pub forked_repos: i64,
+    pub live_repos: i64,
+    pub all_time_languages: HashMap<String, LanguageTotal>,
+    pub recent_languages: HashMap<String, LanguageTotal>,
+    // The language totals of all owned repos by year, and which year of a
+    // repo that is, see `aggregate::LANGUAGE_EVOLUTION_YEAR`.
+    pub languages_by_year: BTreeMap<i32, HashMap<String, LanguageTotal>>,
+    pub languages_by_year_of: String,
+    // How the language weights were computed, e.g. `stars`, and whether that's
+    // anything but plain bytes.
+    pub language_weighting: String,
+    pub languages_weighted: bool,
+    pub repos: Vec<MyRepo>,
+    // The owned repos that aren't live, because they weren't pushed to
+    // lately, so they can still be featured.
+    #[serde(default)]
+    pub dormant_repos: Vec<MyRepo>,
+    // The owned public repos that are archived, by full name.
+    pub archived_repos: Vec<String>,
+    // Every year since the first repo, with the owned repos created and last
+    // pushed to in it.
+    pub repos_by_year: Vec<RepoYear>,
+}
+
+/// How many owned repos were created and last pushed to in a year.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RepoYear {
+    pub year: i32,
+    pub created: i64,
+    pub pushed: i64,
+}
+
+/// The bytes of one language summed over repos, and the same bytes weighted
+/// by the [`weighting::Weighting`] that was used.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LanguageTotal {
+    pub color: String,
+    pub bytes: i64,
+    pub weight: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TopRepos<'a> {
+    pub most_recent: Vec<&'a MyRepo>,
+    pub most_starred: Vec<&'a MyRepo>,
+    pub most_forked: Vec<&'a MyRepo>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LanguageStat<'a> {
+    pub name: &'a str,
+    pub color: &'a str,
+    // The share of the weighted total, and of the plain byte total.
+    pub percentage: i64,
+    pub raw_percentage: i64,
+    pub bytes: String,
+    // The languages in the "Other" entry; empty for all others.
+    pub members: Vec<&'a str>,
+}
+
+/// The top languages of the repos counted toward a year.
+#[derive(Debug, Serialize)]
+pub struct LanguageYear<'a> {
+    pub year: i32,
+    pub languages: Vec<LanguageStat<'a>>,
+}
+
+/// A live repo from `aggregate::FeaturedConfig`, with the line about it.
+#[derive(Debug, Serialize)]
+pub struct FeaturedRepo<'a> {
+    pub blurb: &'a str,
+    pub repo: &'a MyRepo,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FeaturedCategory<'a> {
+    // Empty for the repos without a category.
+    pub name: &'a str,
+    pub repos: Vec<FeaturedRepo<'a>>,
+}
+
+/// How many live repos were last pushed to in a month, e.g. `2024-06`.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct MonthlyPushes {
+    pub month: String,
+    pub repos: i64,
+}
+
+/// The account age and the oldest live repo, for templates.
+#[derive(Debug, Serialize)]
+pub struct Timeline<'a> {
+    // Both unset when no forge said when the account was created.
+    pub account_created_date: Option<String>,
+    pub account_age_years: Option<i64>,
+    // The live repo that was created first.
+    pub oldest_active_repo: Option<&'a MyRepo>,
+}
+
+/// Issue and pull/merge request counts, summed across forges.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct IssueAndPrStats {
+    pub issues_created: i64,
+    pub issues_closed: i64,
+    pub prs_created: i64,
+    pub prs_merged: i64,
+}
+
+/// When the stars of an owned repo were given.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RepoStars {
+    pub full_name: String,
+    pub url: String,
+    // The day of each star as YYYY-MM-DD, oldest first. Stars that were taken
+    // back again aren't in here at all.
+    pub starred_on: Vec<String>,
+}
+
+/// The stars of all owned repos on the forges that record when they were
+/// given.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StarHistory {
+    pub repos: Vec<RepoStars>,
+}
+
+/// How many stars there were at the end of a day.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StarCount {
+    pub date: String,
+    pub stars: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RepoStarGrowth<'a> {
+    pub full_name: &'a str,
+    pub url: &'a str,
+    pub stars: i64,
+    pub gained_last_30_days: i64,
+    pub gained_last_365_days: i64,
+    // The star count on every day a star was given.
+    pub series: Vec<StarCount>,
+}
+
+/// The star history, summed up for templates.
+#[derive(Debug, Serialize)]
+pub struct StarGrowth<'a> {
+    // The total star count on every day a star was given.
+    pub total: Vec<StarCount>,
+    pub gained_last_30_days: i64,
+    pub gained_last_365_days: i64,
+    // Every repo with stars, most starred first.
+    pub repos: Vec<RepoStarGrowth<'a>>,
+    // The repos that gained the most stars lately.
+    pub fastest_growing: Vec<RepoStarGrowth<'a>>,
+}
+
+/// Whether a data source was fetched, or what the template gets instead.
+#[derive(Debug, Default, Serialize)]
+pub struct SourceStatus {
+    // Set when fetching failed and the data is from the snapshot taken on
+    // this date.
+    pub snapshot_date: Option<String>,
+    // Set when fetching failed and there was no snapshot to fall back to.
+    pub unavailable: bool,
+}
+
+impl SourceStatus {
+    pub fn failed(&self) -> bool {
+        self.snapshot_date.is_some() || self.unavailable
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Sources {
+    pub user_and_repo_stats: SourceStatus,
+    pub issue_and_pr_stats: SourceStatus,
+    pub star_history: SourceStatus,
+}
+
+impl Sources {
+    /// Whether any source fell back to a snapshot or has no data, which makes
+    /// the run exit with a distinct status.
+    pub fn degraded(&self) -> bool {
+        self.user_and_repo_stats.failed() || self.issue_and_pr_stats.failed() || self.star_history.failed()
+    }
+}
+
+// Every section is optional, so a failed data source only takes out the
+// sections built from it. Templates check `sources` to render a fallback.
+#[derive(Serialize)]
+pub struct Context<'a> {
+    pub user_and_repo_stats: Option<&'a UserAndRepoStats>,
+    pub top_repos: Option<TopRepos<'a>>,
+    // The repos to show off, from the config, by category.
+    pub featured: Option<Vec<FeaturedCategory<'a>>>,
+    pub issue_and_pr_stats: Option<&'a IssueAndPrStats>,
+    pub top_all_time_languages: Option<Vec<LanguageStat<'a>>>,
+    pub top_recent_languages: Option<Vec<LanguageStat<'a>>>,
+    // The same, with the languages of each of `aggregate::LANGUAGE_GROUPS`
+    // shown as one.
+    pub top_all_time_language_groups: Option<Vec<LanguageStat<'a>>>,
+    pub top_recent_language_groups: Option<Vec<LanguageStat<'a>>>,
+    // The top languages of each year, oldest first.
+    pub language_evolution: Option<Vec<LanguageYear<'a>>>,
+    pub push_activity: Option<Vec<MonthlyPushes>>,
+    pub timeline: Option<Timeline<'a>>,
+    pub star_history: Option<StarGrowth<'a>>,
+    pub sources: Sources,
+    // What relative dates are relative to.
+    #[serde(skip)]
+    pub now: DateTime<Utc>,
+}
+
+impl<'a> Context<'a> {
+    /// Builds every section the available data allows. `language_groups` are
+    /// the language totals of `user_and_repo_stats`, grouped, and `featured`
+    /// the repos to show off.
+    pub fn new(
+        user_and_repo_stats: Option<&'a UserAndRepoStats>,
+        language_groups: Option<&'a LanguageGroupTotals>,
+        featured: &[aggregate::FeaturedConfig<'a>],
+        issue_and_pr_stats: Option<&'a IssueAndPrStats>,
+        star_history: Option<&'a StarHistory>,
+        sources: Sources,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let top_languages = |languages| aggregate::top_languages(languages, aggregate::LANGUAGE_CUTOFF);
+        let top_all_time_languages = user_and_repo_stats.map(|s| top_languages(&s.all_time_languages));
+        tracing::debug!("{top_all_time_languages:#?}");
+        let top_recent_languages = user_and_repo_stats.map(|s| top_languages(&s.recent_languages));
+        tracing::debug!("{top_recent_languages:#?}");
+        Self {
+            user_and_repo_stats,
+            top_repos: user_and_repo_stats.map(|s| aggregate::top_repos(&s.repos)),
+            featured: user_and_repo_stats.map(|s| aggregate::featured_repos(featured, s)),
+            issue_and_pr_stats,
+            top_all_time_languages,
+            top_recent_languages,
+            top_all_time_language_groups: language_groups.map(|g| top_languages(&g.all_time)),
+            top_recent_language_groups: language_groups.map(|g| top_languages(&g.recent)),
+            language_evolution: user_and_repo_stats
+                .map(|s| aggregate::language_evolution(&s.languages_by_year, aggregate::LANGUAGE_CUTOFF)),
+            push_activity: user_and_repo_stats.map(|s| aggregate::push_activity(&s.repos, now)),
+            timeline: user_and_repo_stats.map(|s| aggregate::timeline(s, now)),
+            star_history: star_history.map(|h| aggregate::star_growth(h, now)),
+            sources,
+            now,
+        }
+    }
+}
+
+/// The language totals of [`UserAndRepoStats`] with the languages of each of
+/// `aggregate::LANGUAGE_GROUPS` merged into one.
+#[derive(Debug)]
+pub struct LanguageGroupTotals {
+    pub all_time: HashMap<String, LanguageTotal>,
+    pub recent: HashMap<String, LanguageTotal>,
+}
+
+impl LanguageGroupTotals {
+    pub fn new(stats: &UserAndRepoStats) -> Self {
+        Self {
+            all_time: aggregate::group_languages(&stats.all_time_languages, aggregate::LANGUAGE_GROUPS),
+            recent: aggregate::group_languages(&stats.recent_languages, aggregate::LANGUAGE_GROUPS),
+        }
+    }
+}
+
+// The README also gets its charts pre-rendered, as `mermaid.*`.
+#[derive(Serialize)]
+struct ReadmeContext<'a> {
+    #[serde(flatten)]
+    context: &'a Context<'a>,
+    mermaid: mermaid::Charts,
+}
+
+impl<'a> ReadmeContext<'a> {
+    fn new(context: &'a Context<'a>) -> Self {
+        Self {
+            context,
+            mermaid: mermaid::Charts::new(context),
+        }
+    }
+}
+
+/// Renders the README from `context` with the built-in template.
+pub fn render_readme(context: &Context<'_>) -> Result<String> {
+    render_tinytemplate(README_TEMPLATE, context)
+}
+
+/// Renders the README from `context` with the template at `path`. Templates
+/// ending in `.jinja` are rendered with minijinja, which needs the `jinja`
+/// feature, and all others with TinyTemplate.
+pub fn render_readme_from(path: &Path, context: &Context<'_>) -> Result<String> {
+    if path.extension().is_some_and(|e| e == "jinja") {
+        #[cfg(feature = "jinja")]
+        return jinja::render(path, &ReadmeContext::new(context), context.now);
+        #[cfg(not(feature = "jinja"))]
+        anyhow::bail!("{} is a Jinja template, but the `jinja` feature is not enabled", path.display());
+    }
+    let template = std::fs::read_to_string(path)
+        .map_err(|e| anyhow::anyhow!("Could not read template {}: {e}", path.display()))?;
+    render_tinytemplate(&template, context)
+}
+
+/// The context as templates see it, e.g. for [`schema::tree`].
+pub fn readme_context_json(context: &Context<'_>) -> Result<serde_json::Value> {
+    Ok(serde_json::to_value(ReadmeContext::new(context))?)
+}
+
+fn render_tinytemplate(template: &str, context: &Context<'_>) -> Result<String> {
+    let mut tt = TinyTemplate::new();
+    formatters::register(&mut tt, context.now);
+    tt.add_template("readme", template)?;
+    Ok(tt.render("readme", &ReadmeContext::new(context))?)
+}
+
+/// Configures the forges a [`Client`] collects from.
+pub struct ClientBuilder {
+    user_agent: String,
+    forges: Vec<Box<dyn Forge>>,
+    local_checkouts: Option<(PathBuf, Counting)>,
+    weighting: Weighting,
+    bot_authors: RegexSet,
+}
+
+impl ClientBuilder {
+    /// Adds GitHub, with `login` as the user whose stats are collected.
+    pub fn github(mut self, login: &str, token: &str) -> Result<Self> {
+        let github = GitHubForge::new(forge::github::API_URL, login, token, &self.user_agent)?;
+        self.forges.push(Box::new(github));
+        Ok(self)
+    }
+
+    /// Adds the forges configured via env vars, see
+    /// [`forge::extra_forges_from_env`].
+    pub fn extra_forges_from_env(mut self) -> Result<Self> {
+        self.forges.extend(forge::extra_forges_from_env(&self.user_agent)?);
+        Ok(self)
+    }
+
+    /// Adds any other [`Forge`].
+    pub fn forge(mut self, forge: Box<dyn Forge>) -> Self {
+        self.forges.push(forge);
+        self
+    }
+
+    /// Counts languages in the local checkouts below `dir` instead of using
+    /// the API numbers, for the repos that have one.
+    pub fn local_checkouts(mut self, dir: impl Into<PathBuf>, counting: Counting) -> Self {
+        self.local_checkouts = Some((dir.into(), counting));
+        self
+    }
+
+    /// How repos are weighted in the language stats. Defaults to plain bytes.
+    pub fn language_weighting(mut self, weighting: Weighting) -> Self {
+        self.weighting = weighting;
+        self
+    }
+
+    /// Commit authors matching any of these regexes (case-insensitive) count
+    /// as bots too, on top of [`forge::DEFAULT_BOT_AUTHOR_PATTERNS`]. Bot
+    /// commits don't make a repo recent.
+    pub fn bot_authors(mut self, patterns: &[impl AsRef<str>]) -> Result<Self> {
+        self.bot_authors = forge::bot_authors(patterns)?;
+        Ok(self)
+    }
+
+    pub fn build(self) -> Client {
+        Client {
+            forges: self.forges,
+            local_checkouts: self.local_checkouts,
+            weighting: self.weighting,
+            bot_authors: self.bot_authors,
+        }
+    }
+}
+
+/// Collects stats from all configured forges.
+pub struct Client {
+    forges: Vec<Box<dyn Forge>>,
+    local_checkouts: Option<(PathBuf, Counting)>,
+    weighting: Weighting,
+    bot_authors: RegexSet,
+}
+
+impl Client {
+    pub fn builder(user_agent: impl Into<String>) -> ClientBuilder {
+        ClientBuilder {
+            user_agent: user_agent.into(),
+            forges: vec![],
+            local_checkouts: None,
+            weighting: Weighting::default(),
+            bot_authors: forge::bot_authors(&[] as &[&str]).expect("DEFAULT_BOT_AUTHOR_PATTERNS are valid"),
+        }
+    }
+
+    /// Fetches the repos of every forge, merges mirrors and aggregates them.
+    /// Recency is judged as of `now`, which should be the `now` the README is
+    /// rendered with.
+    pub async fn user_and_repo_stats(&self, now: DateTime<Utc>) -> Result<UserAndRepoStats> {
+        timed("user and repo stats", async {
+            let users = join_all(self.forges.iter().map(|forge| {
+                let source = format!("{} repos", forge.name());
+                async move { timed(&source, forge.user_repos()).await }
+            }))
+            .await;
+
+            let mut created_at = String::new();
+            let mut repos = vec![];
+            for (forge, user) in self.forges.iter().zip(users) {
+                let user = user?;
+                tracing::info!("Got {} repos from {}", user.repos.len(), forge.name());
+                if let Some(user_created_at) = user.created_at {
+                    // The oldest account is the one that tells the story.
+                    if created_at.is_empty() || user_created_at < created_at {
+                        created_at = user_created_at;
+                    }
+                }
+                repos.extend(user.repos);
+            }
+            // Before merging, while each repo has only its own recent commits.
+            forge::set_last_human_commits(&mut repos, &self.bot_authors);
+            let mut repos = forge::merge_mirrored_repos(repos);
+            if let Some((dir, counting)) = self.local_checkouts.clone() {
+                // Scanning checkouts shells out to git a lot, so keep it off the
+                // threads that drive the other requests.
+                let scan = tokio::task::spawn_blocking(move || {
+                    use_local_languages(&dir, &counting, &mut repos);
+                    repos
+                });
+                repos = timed("local language stats", async { Ok(scan.await?) }).await?;
+            }
+            let mut stats = aggregate::collect_user_repo_stats(&repos, now, self.weighting)?;
+            stats.created_at = created_at;
+            Ok(stats)
+        })
+        .await
+    }
+
+    /// Fetches when the stars of the owned repos on every forge were given.
+    pub async fn star_history(&self) -> Result<StarHistory> {
+        timed("star history", async {
+            let all_repos = join_all(self.forges.iter().map(|forge| {
+                let source = format!("{} star history", forge.name());
+                async move { timed(&source, forge.star_history()).await }
+            }))
+            .await;
+
+            let mut history = StarHistory::default();
+            for repos in all_repos {
+                history.repos.extend(repos?);
+            }
+            history.repos.sort_by(|a, b| a.full_name.cmp(&b.full_name));
+            Ok(history)
+        })
+        .await
+    }
+
+    /// Fetches the issue and PR counts of every forge and sums them.
+    pub async fn issue_and_pr_stats(&self) -> Result<IssueAndPrStats> {
+        timed("issue and PR stats", async {
+            let all_stats = join_all(self.forges.iter().map(|forge| {
+                let source = format!("{} issue and PR stats", forge.name());
+                async move { timed(&source, forge.issue_and_pr_stats()).await }
+            }))
+            .await;
+
+            let mut stats = IssueAndPrStats::default();
+            for (forge, forge_stats) in self.forges.iter().zip(all_stats) {
+                let forge_stats = forge_stats?;
+                tracing::debug!("{} {forge_stats:#?}", forge.name());
+                stats.issues_created += forge_stats.issues_created;
+                stats.issues_closed += forge_stats.issues_closed;
+                stats.prs_created += forge_stats.prs_created;
+                stats.prs_merged += forge_stats.prs_merged;
+            }
+            Ok(stats)
+        })
+        .await
+    }
+}
+
+/// Stores freshly fetched data in `snapshotted`, or keeps the previous
+/// snapshot (if any) when fetching failed.
+pub fn with_fallback<T>(source: &str, result: Result<T>, snapshotted: &mut Option<Snapshotted<T>>) -> SourceStatus {
+    match result {
+        Ok(data) => {
+            *snapshotted = Some(Snapshotted::now(data));
+            SourceStatus::default()
+        }
+        Err(e) => match snapshotted {
+            Some(s) => {
+                tracing::warn!("Using the snapshot from {} for {}: {}", s.taken_at, source, e);
+                SourceStatus {
+                    snapshot_date: Some(s.taken_at.clone()),
+                    unavailable: false,
+                }
+            }
+            None => {
+                tracing::warn!("No data available for {}: {}", source, e);
+                SourceStatus {
+                    snapshot_date: None,
+                    unavailable: true,
+                }
+            }
+        },
+    }
+}
+
+async fn timed<T>(source: &str, fut: impl Future<Output = Result<T>>) -> Result<T> {
+    let start = Instant::now();
+    let result = fut.await;
+    match &result {
+        Ok(_) => tracing::info!("Fetched {} in {:.2?}", source, start.elapsed()),
+        Err(e) => tracing::error!("Fetching {} failed after {:.2?}: {}", source, start.elapsed(), e),
+    }
+    result
+}
+
+// Replaces the API language numbers with the ones counted in local checkouts
+// below `dir`. Repos without a checkout keep the API numbers.
+fn use_local_languages(dir: &Path, counting: &Counting, repos: &mut [forge::Repo]) {
+    for repo in repos {
+        let Some(checkout) = local_languages::find_checkout(dir, &repo.full_name) else {
+            tracing::info!("No local checkout for {}; using API language stats", repo.full_name);
+            continue;
+        };
+        match local_languages::languages_in_checkout(&checkout, counting) {
+            Ok(languages) => {
+                tracing::debug!("Local language stats for {}: {languages:?}", repo.full_name);
+                repo.languages = languages;
+            }
+            Err(e) => tracing::warn!(
+                "Could not count languages in {}: {}; using API language stats",
+                checkout.display(),
+                e
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    // Fails right away, or succeeds after a while and notes that it did.
+    struct TestForge {
+        fails: bool,
+        finished: Arc<AtomicBool>,
+    }
+
+    #[async_trait]
+    impl Forge for TestForge {
+        fn name(&self) -> &str {
+            "Test"
+        }
+
+        async fn user_repos(&self) -> Result<forge::User> {
+            self.finish().await?;
+            Ok(forge::User::default())
+        }
+
+        async fn issue_and_pr_stats(&self) -> Result<IssueAndPrStats> {
+            self.finish().await?;
+            Ok(IssueAndPrStats::default())
+        }
+    }
+
+    impl TestForge {
+        async fn finish(&self) -> Result<()> {
+            if self.fails {
+                anyhow::bail!("forge is down");
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            self.finished.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    fn client(finished: &Arc<AtomicBool>) -> Client {
+        let forge = |fails| {
+            Box::new(TestForge {
+                fails,
+                finished: Arc::clone(finished),
+            })
+        };
+        Client::builder("test").forge(forge(true)).forge(forge(false)).build()
+    }
+
+    #[test]
+    fn fresh_data_replaces_the_snapshot() {
+        let mut snapshotted = Some(Snapshotted {
+            taken_at: "2024-01-01".to_string(),
+            data: 1,
+        });
+        let status = with_fallback("test", Ok(2), &mut snapshotted);
+        assert!(!status.failed());
+        assert_eq!(snapshotted.map(|s| s.data), Some(2));
+    }
+
+    #[test]
+    fn failures_fall_back_to_the_snapshot() {
+        let mut snapshotted = Some(Snapshotted {
+            taken_at: "2024-01-01".to_string(),
+            data: 1,
+        });
+        let status = with_fallback("test", Err(anyhow::anyhow!("down")), &mut snapshotted);
+        assert_eq!(status.snapshot_date.as_deref(), Some("2024-01-01"));
+        assert!(!status.unavailable);
+        assert!(status.failed());
+        assert_eq!(snapshotted.map(|s| s.data), Some(1));
+    }
+
+    #[test]
+    fn failures_without_a_snapshot_are_unavailable() {
+        let mut snapshotted: Option<Snapshotted<i32>> = None;
+        let status = with_fallback("test", Err(anyhow::anyhow!("down")), &mut snapshotted);
+        assert_eq!(status.snapshot_date, None);
+        assert!(status.unavailable);
+        assert!(snapshotted.is_none());
+    }
+
+    #[test]
+    fn any_failed_source_degrades_the_run() {
+        assert!(!Sources::default().degraded());
+        let unavailable = SourceStatus {
+            snapshot_date: None,
+            unavailable: true,
+        };
+        let sources = Sources {
+            star_history: unavailable,
+            ..Default::default()
+        };
+        assert!(sources.degraded());
+    }
+
+    #[test]
+    fn unavailable_sources_are_noted() {
+        let sources = Sources {
+            user_and_repo_stats: SourceStatus {
+                snapshot_date: None,
+                unavailable: true,
+            },
+            star_history: SourceStatus {
+                snapshot_date: None,
+                unavailable: true,
+            },
+            ..Default::default()
+        };
+        let context = Context::new(None, None, &[], None, None, sources, Utc::now());
+        let readme = render_readme(&context).unwrap();
+        assert!(readme.contains("_Repo stats are currently unavailable._"), "{readme}");
+        assert!(readme.contains("_The star history is currently unavailable._"), "{readme}");
+    }
+
+    #[tokio::test]
+    async fn a_failing_forge_doesnt_cancel_the_others() {
+        let finished = Arc::new(AtomicBool::new(false));
+        assert!(client(&finished).user_and_repo_stats(Utc::now()).await.is_err());
+        assert!(finished.load(Ordering::SeqCst));
+
+        let finished = Arc::new(AtomicBool::new(false));
+        assert!(client(&finished).issue_and_pr_stats().await.is_err());
+        assert!(finished.load(Ordering::SeqCst));
+    }
+
+    struct OneRepoForge;
+
+    #[async_trait]
+    impl Forge for OneRepoForge {
+        fn name(&self) -> &str {
+            "Test"
+        }
+
+        async fn user_repos(&self) -> Result<forge::User> {
+            Ok(forge::User {
+                created_at: None,
+                repos: vec![forge::Repo {
+                    full_name: "AndreasOM/omt".to_string(),
+                    created_at: Some("2019-01-01T00:00:00Z".to_string()),
+                    pushed_at: Some("2023-03-01T00:00:00Z".to_string()),
+                    ..Default::default()
+                }],
+            })
+        }
+
+        async fn issue_and_pr_stats(&self) -> Result<IssueAndPrStats> {
+            Ok(IssueAndPrStats::default())
+        }
+    }
+
+    #[tokio::test]
+    async fn repo_recency_is_judged_as_of_now() {
+        let client = Client::builder("test").forge(Box::new(OneRepoForge)).build();
+        let at = |date: &str| DateTime::parse_from_rfc3339(date).unwrap().with_timezone(&Utc);
+        let stats = client.user_and_repo_stats(at("2024-06-15T00:00:00Z")).await.unwrap();
+        assert_eq!(stats.live_repos, 1);
+        let stats = client.user_and_repo_stats(at("2025-06-15T00:00:00Z")).await.unwrap();
+        assert_eq!(stats.live_repos, 0);
+    }
+}
@@ -0,0 +1,115 @@
+// Language metadata from github-linguist: each language's type, color and
+// aliases. Forges only report names (and GitHub colors), so this is what
+// tells us whether a language is code or e.g. data, and which names are the
+// same language.
+use once_cell::sync::Lazy;
+use std::{collections::HashMap, str::FromStr};
+
+const LANGUAGES_TSV: &str = include_str!("../linguist/languages.tsv");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LanguageType {
+    Programming,
+    Markup,
+    Data,
+    Prose,
+}
+
+impl FromStr for LanguageType {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "programming" => Ok(Self::Programming),
+            "markup" => Ok(Self::Markup),
+            "data" => Ok(Self::Data),
+            "prose" => Ok(Self::Prose),
+            _ => Err(anyhow::anyhow!("Unknown language type `{s}`")),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct LanguageInfo {
+    pub name: &'static str,
+    pub kind: LanguageType,
+    pub color: Option<&'static str>,
+    pub aliases: Vec<&'static str>,
+}
+
+static LANGUAGES: Lazy<Vec<LanguageInfo>> = Lazy::new(|| {
+    LANGUAGES_TSV
+        .lines()
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(|line| {
+            let fields: Vec<&str> = line.split('\t').collect();
+            let [name, kind, color, aliases] = fields[..] else {
+                panic!("Expected 4 fields in languages.tsv, got `{line}`");
+            };
+            LanguageInfo {
+                name,
+                kind: kind.parse().unwrap_or_else(|e| panic!("{e} in languages.tsv")),
+                color: (!color.is_empty()).then_some(color),
+                aliases: aliases.split(',').filter(|a| !a.is_empty()).collect(),
+            }
+        })
+        .collect()
+});
+
+// Every language by its lowercased name, the name with dashes for spaces (as
+// linguist does it) and its aliases.
+static BY_ALIAS: Lazy<HashMap<String, &'static LanguageInfo>> = Lazy::new(|| {
+    let mut index = HashMap::new();
+    for language in LANGUAGES.iter() {
+        let name = language.name.to_lowercase();
+        index.insert(name.replace(' ', "-"), language);
+        index.insert(name, language);
+        for alias in &language.aliases {
+            index.insert(alias.to_string(), language);
+        }
+    }
+    index
+});
+
+/// Finds a language by its name or one of its aliases, ignoring case.
+pub fn lookup(name: &str) -> Option<&'static LanguageInfo> {
+    let name = name.to_lowercase();
+    BY_ALIAS
+        .get(&name)
+        .or_else(|| BY_ALIAS.get(&name.replace(' ', "-")))
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn database_is_well_formed() {
+        let mut seen = HashMap::new();
+        for language in LANGUAGES.iter() {
+            if let Some(color) = language.color {
+                assert!(
+                    color.len() == 7 && color.starts_with('#') && color[1..].chars().all(|c| c.is_ascii_hexdigit()),
+                    "{}: {color}",
+                    language.name
+                );
+            }
+            for alias in &language.aliases {
+                assert_eq!(*alias, alias.to_lowercase(), "{}", language.name);
+                assert!(seen.insert(*alias, language.name).is_none(), "{alias} is used twice");
+            }
+        }
+    }
+
+    #[test]
+    fn lookup_by_name_and_alias() {
+        assert_eq!(lookup("Rust").map(|l| l.name), Some("Rust"));
+        assert_eq!(lookup("rust").map(|l| l.name), Some("Rust"));
+        assert_eq!(lookup("Perl 6").map(|l| l.name), Some("Raku"));
+        assert_eq!(lookup("yml").map(|l| (l.name, l.kind)), Some(("YAML", LanguageType::Data)));
+        assert_eq!(lookup("Jupyter Notebook").map(|l| l.kind), Some(LanguageType::Markup));
+        assert_eq!(lookup("Text").and_then(|l| l.color), None);
+        assert!(lookup("Klingon").is_none());
+    }
+}
@@ -0,0 +1,532 @@
+// Counts language bytes in local git checkouts, as an alternative to the
+// `languages` edges from the API. Those only cover the default branch and
+// include everything vendored into a repo, which is why
+// `REPOS_TO_IGNORE_FOR_LANGUAGE_STATS` exists.
+//
+// Only files tracked by git are counted. They are classified by file name,
+// extension and shebang, and `.gitattributes` can mark them as
+// `linguist-vendored`, `linguist-generated` (both are skipped), or override
+// their language with `linguist-language`.
+//
+// Instead of whole files, we can also count only the lines `git blame`
+// attributes to us, so code contributed by others doesn't skew the stats.
+use anyhow::Result;
+use std::{
+    collections::HashMap,
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::{forge::Language, linguist};
+
+// How many bytes to look at when deciding whether a file is binary. This is
+// the same heuristic git uses.
+const BINARY_SNIFF_BYTES: usize = 8000;
+
+// Directories that linguist treats as vendored unless `.gitattributes` says
+// otherwise.
+const DEFAULT_VENDORED_DIRS: &[&str] = &["vendor", "vendored", "node_modules", "third_party", "thirdparty", "external"];
+
+struct LanguageDef {
+    name: &'static str,
+    extensions: &'static [&'static str],
+    filenames: &'static [&'static str],
+    interpreters: &'static [&'static str],
+}
+
+// Names are linguist's, which has their colors. Data and prose languages such
+// as JSON, YAML and Markdown aren't listed, because GitHub doesn't count them
+// either.
+const LANGUAGES: &[LanguageDef] = &[
+    LanguageDef { name: "Assembly", extensions: &["asm", "s", "nasm"], filenames: &[], interpreters: &[] },
+    LanguageDef { name: "C", extensions: &["c", "h"], filenames: &[], interpreters: &[] },
+    LanguageDef { name: "C#", extensions: &["cs"], filenames: &[], interpreters: &[] },
+    LanguageDef { name: "C++", extensions: &["cpp", "cc", "cxx", "hpp", "hh", "hxx", "inl"], filenames: &[], interpreters: &[] },
+    LanguageDef { name: "CMake", extensions: &["cmake"], filenames: &["CMakeLists.txt"], interpreters: &[] },
+    LanguageDef { name: "CSS", extensions: &["css"], filenames: &[], interpreters: &[] },
+    LanguageDef { name: "Dart", extensions: &["dart"], filenames: &[], interpreters: &[] },
+    LanguageDef { name: "Dockerfile", extensions: &["dockerfile"], filenames: &["Dockerfile"], interpreters: &[] },
+    LanguageDef { name: "Go", extensions: &["go"], filenames: &[], interpreters: &[] },
+    LanguageDef { name: "GLSL", extensions: &["glsl", "vert", "frag", "geom", "comp"], filenames: &[], interpreters: &[] },
+    LanguageDef { name: "HLSL", extensions: &["hlsl", "fx"], filenames: &[], interpreters: &[] },
+    LanguageDef { name: "HTML", extensions: &["html", "htm", "xhtml"], filenames: &[], interpreters: &[] },
+    LanguageDef { name: "Java", extensions: &["java"], filenames: &[], interpreters: &[] },
+    LanguageDef { name: "JavaScript", extensions: &["js", "mjs", "cjs", "jsx"], filenames: &[], interpreters: &["node", "nodejs"] },
+    LanguageDef { name: "Kotlin", extensions: &["kt", "kts"], filenames: &[], interpreters: &[] },
+    LanguageDef { name: "Lua", extensions: &["lua"], filenames: &[], interpreters: &["lua", "luajit"] },
+    LanguageDef { name: "Makefile", extensions: &["mk", "mak"], filenames: &["Makefile", "GNUmakefile", "makefile"], interpreters: &["make"] },
+    LanguageDef { name: "Objective-C", extensions: &["m"], filenames: &[], interpreters: &[] },
+    LanguageDef { name: "Objective-C++", extensions: &["mm"], filenames: &[], interpreters: &[] },
+    LanguageDef { name: "Perl", extensions: &["pl", "pm", "t"], filenames: &[], interpreters: &["perl"] },
+    LanguageDef { name: "PHP", extensions: &["php"], filenames: &[], interpreters: &["php"] },
+    LanguageDef { name: "PowerShell", extensions: &["ps1", "psm1"], filenames: &[], interpreters: &["pwsh"] },
+    LanguageDef { name: "Python", extensions: &["py", "pyw"], filenames: &[], interpreters: &["python"] },
+    LanguageDef { name: "Raku", extensions: &["raku", "rakumod", "p6", "pm6"], filenames: &[], interpreters: &["raku", "perl6"] },
+    LanguageDef { name: "Ruby", extensions: &["rb", "rake"], filenames: &["Rakefile", "Gemfile"], interpreters: &["ruby"] },
+    LanguageDef { name: "Rust", extensions: &["rs"], filenames: &[], interpreters: &[] },
+    LanguageDef { name: "SCSS", extensions: &["scss"], filenames: &[], interpreters: &[] },
+    LanguageDef { name: "Shell", extensions: &["sh", "bash", "zsh"], filenames: &[], interpreters: &["sh", "bash", "zsh", "dash", "ksh"] },
+    LanguageDef { name: "Swift", extensions: &["swift"], filenames: &[], interpreters: &[] },
+    LanguageDef { name: "TypeScript", extensions: &["ts", "tsx", "mts", "cts"], filenames: &[], interpreters: &["deno", "ts-node"] },
+    LanguageDef { name: "WGSL", extensions: &["wgsl"], filenames: &[], interpreters: &[] },
+    LanguageDef { name: "XS", extensions: &["xs"], filenames: &[], interpreters: &[] },
+    LanguageDef { name: "Zig", extensions: &["zig"], filenames: &[], interpreters: &[] },
+];
+
+/// Finds the checkout for `full_name` below `root`, either as
+/// `root/owner/name` or as `root/name`.
+pub fn find_checkout(root: &Path, full_name: &str) -> Option<PathBuf> {
+    let name = full_name.rsplit('/').next().unwrap_or(full_name);
+    [root.join(full_name), root.join(name)]
+        .into_iter()
+        .find(|p| p.join(".git").exists())
+}
+
+/// What to count for each file in a checkout.
+#[derive(Debug, Clone)]
+pub enum Counting {
+    /// The size of the whole file.
+    AllBytes,
+    /// The bytes of the lines that `git blame` attributes to one of these
+    /// author emails.
+    AuthoredBy(Vec<String>),
+}
+
+/// Returns the language bytes of the files tracked in the checkout at `dir`.
+pub fn languages_in_checkout(dir: &Path, counting: &Counting) -> Result<Vec<Language>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["ls-files", "-z"])
+        .output()?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "git ls-files failed in {}: {}",
+            dir.display(),
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    let files: Vec<String> = output
+        .stdout
+        .split(|b| *b == 0)
+        .filter(|f| !f.is_empty())
+        .map(|f| String::from_utf8_lossy(f).into_owned())
+        .collect();
+
+    let attributes = GitAttributes::load(dir, &files);
+    let mut sizes: HashMap<&str, i64> = HashMap::new();
+    for file in &files {
+        let attrs = attributes.for_path(file);
+        if attrs.vendored.unwrap_or_else(|| is_vendored_by_default(file)) || attrs.generated.unwrap_or(false) {
+            tracing::trace!("Skipping vendored or generated file {file}");
+            continue;
+        }
+        let path = dir.join(file);
+        let Ok(metadata) = fs::metadata(&path) else {
+            // Deleted in the working tree, or a submodule.
+            continue;
+        };
+        if !metadata.is_file() {
+            continue;
+        }
+        let language = match attrs.language {
+            Some(name) => language_by_name(&name),
+            None => match language_by_path(file) {
+                Some(l) => Some(l),
+                None => language_by_shebang(&path),
+            },
+        };
+        let Some(language) = language else { continue };
+        if is_binary(&path) {
+            continue;
+        }
+        let bytes = match counting {
+            Counting::AllBytes => metadata.len() as i64,
+            Counting::AuthoredBy(emails) => match authored_bytes(dir, file, emails) {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    // E.g. staged but not committed yet; the other files still count.
+                    tracing::warn!("Skipping {}: {}", file, e);
+                    continue;
+                }
+            },
+        };
+        *sizes.entry(language.name).or_default() += bytes;
+    }
+
+    Ok(sizes
+        .into_iter()
+        .map(|(name, bytes)| Language {
+            name: name.to_string(),
+            color: linguist::lookup(name).and_then(|l| l.color).map(str::to_string),
+            bytes,
+        })
+        .collect())
+}
+
+/// Sums the bytes of the lines in `file` last touched by one of `emails`,
+/// ignoring whitespace-only changes.
+fn authored_bytes(dir: &Path, file: &str, emails: &[impl AsRef<str>]) -> Result<i64> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["blame", "-w", "--line-porcelain", "HEAD", "--"])
+        .arg(file)
+        .output()?;
+    if !output.status.success() {
+        return Err(anyhow::anyhow!(
+            "git blame failed for {} in {}: {}",
+            file,
+            dir.display(),
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    Ok(sum_authored_bytes(&output.stdout, emails))
+}
+
+// In the line porcelain format every line of the file comes with a full
+// header, and the line itself follows the header prefixed with a tab.
+fn sum_authored_bytes(porcelain: &[u8], emails: &[impl AsRef<str>]) -> i64 {
+    let mut bytes = 0;
+    let mut is_ours = false;
+    for line in porcelain.split(|b| *b == b'\n') {
+        if let Some(content) = line.strip_prefix(b"\t") {
+            if is_ours {
+                // Count the newline as well, like the file size does.
+                bytes += content.len() as i64 + 1;
+            }
+        } else if let Some(mail) = line.strip_prefix(b"author-mail ") {
+            let mail = String::from_utf8_lossy(mail);
+            is_ours = is_author(mail.trim_start_matches('<').trim_end_matches('>'), emails);
+        }
+    }
+    bytes
+}
+
+// GitHub's noreply addresses are prefixed with the account id for newer
+// accounts, e.g. `12345+login@users.noreply.github.com`, so we ignore that.
+fn is_author(mail: &str, emails: &[impl AsRef<str>]) -> bool {
+    let mail = match mail.split_once('+') {
+        Some((id, rest)) if id.chars().all(|c| c.is_ascii_digit()) && rest.ends_with("@users.noreply.github.com") => rest,
+        _ => mail,
+    };
+    emails.iter().any(|e| e.as_ref().eq_ignore_ascii_case(mail))
+}
+
+fn language_by_name(name: &str) -> Option<&'static LanguageDef> {
+    let found = LANGUAGES.iter().find(|l| l.name.eq_ignore_ascii_case(name));
+    if found.is_none() {
+        tracing::warn!("Unknown language '{}' in linguist-language attribute", name);
+    }
+    found
+}
+
+fn language_by_path(path: &str) -> Option<&'static LanguageDef> {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    if let Some(l) = LANGUAGES.iter().find(|l| l.filenames.contains(&file_name)) {
+        return Some(l);
+    }
+    let (_, ext) = file_name.rsplit_once('.')?;
+    let ext = ext.to_ascii_lowercase();
+    LANGUAGES.iter().find(|l| l.extensions.contains(&ext.as_str()))
+}
+
+fn language_by_shebang(path: &Path) -> Option<&'static LanguageDef> {
+    let mut buf = [0; 128];
+    let n = fs::File::open(path).ok()?.read(&mut buf).ok()?;
+    let first_line = std::str::from_utf8(&buf[..n]).ok()?.lines().next()?;
+    let interpreter = interpreter_from_shebang(first_line)?;
+    LANGUAGES.iter().find(|l| l.interpreters.contains(&interpreter.as_str()))
+}
+
+/// Extracts the interpreter from a shebang line, looking through `env` and
+/// dropping version suffixes, so `#!/usr/bin/env python3` yields `python`.
+fn interpreter_from_shebang(line: &str) -> Option<String> {
+    let mut words = line.strip_prefix("#!")?.split_whitespace();
+    let mut program = words.next()?.rsplit('/').next()?;
+    if program == "env" {
+        program = words.find(|w| !w.starts_with('-'))?;
+    }
+    Some(program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.').to_string())
+}
+
+fn is_binary(path: &Path) -> bool {
+    let mut buf = vec![0; BINARY_SNIFF_BYTES];
+    match fs::File::open(path).and_then(|mut f| f.read(&mut buf)) {
+        Ok(n) => buf[..n].contains(&0),
+        Err(_) => true,
+    }
+}
+
+fn is_vendored_by_default(path: &str) -> bool {
+    let mut dirs = path.split('/');
+    dirs.next_back();
+    dirs.any(|d| DEFAULT_VENDORED_DIRS.contains(&d))
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Attributes {
+    vendored: Option<bool>,
+    generated: Option<bool>,
+    language: Option<String>,
+}
+
+struct AttributeRule {
+    // The directory of the `.gitattributes` file, with a trailing slash, or
+    // empty for the root.
+    base: String,
+    pattern: String,
+    attributes: Attributes,
+}
+
+struct GitAttributes {
+    rules: Vec<AttributeRule>,
+}
+
+impl GitAttributes {
+    fn load(dir: &Path, files: &[String]) -> Self {
+        let mut attribute_files: Vec<&String> = files
+            .iter()
+            .filter(|f| *f == ".gitattributes" || f.ends_with("/.gitattributes"))
+            .collect();
+        // Deeper files take precedence, so they have to come last.
+        attribute_files.sort_by_key(|f| f.matches('/').count());
+
+        let mut rules = vec![];
+        for file in attribute_files {
+            let base = file.trim_end_matches(".gitattributes").to_string();
+            match fs::read_to_string(dir.join(file)) {
+                Ok(content) => rules.extend(parse_gitattributes(&base, &content)),
+                Err(e) => tracing::warn!("Could not read {}: {}", dir.join(file).display(), e),
+            }
+        }
+        Self { rules }
+    }
+
+    fn for_path(&self, path: &str) -> Attributes {
+        let mut attributes = Attributes::default();
+        for rule in &self.rules {
+            let Some(relative) = path.strip_prefix(&rule.base) else {
+                continue;
+            };
+            if !pattern_matches(&rule.pattern, relative) {
+                continue;
+            }
+            if rule.attributes.vendored.is_some() {
+                attributes.vendored = rule.attributes.vendored;
+            }
+            if rule.attributes.generated.is_some() {
+                attributes.generated = rule.attributes.generated;
+            }
+            if rule.attributes.language.is_some() {
+                attributes.language = rule.attributes.language.clone();
+            }
+        }
+        attributes
+    }
+}
+
+fn parse_gitattributes(base: &str, content: &str) -> Vec<AttributeRule> {
+    let mut rules = vec![];
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut words = line.split_whitespace();
+        let Some(pattern) = words.next() else { continue };
+        let mut attributes = Attributes::default();
+        for attr in words {
+            let (name, value) = match attr.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (attr.trim_start_matches(['-', '!']), None),
+            };
+            let set = !attr.starts_with(['-', '!']) && value != Some("false");
+            match name {
+                "linguist-vendored" => attributes.vendored = Some(set),
+                "linguist-generated" => attributes.generated = Some(set),
+                "linguist-language" => attributes.language = value.map(str::to_string),
+                _ => {}
+            }
+        }
+        if attributes != Attributes::default() {
+            rules.push(AttributeRule {
+                base: base.to_string(),
+                pattern: pattern.to_string(),
+                attributes,
+            });
+        }
+    }
+    rules
+}
+
+/// Matches a `.gitattributes` pattern against a path relative to the
+/// directory of the attributes file. Patterns without a slash match the file
+/// name at any depth, everything else is anchored.
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let pattern = pattern.strip_prefix('/').unwrap_or(pattern);
+    if !pattern.contains('/') {
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+        return glob_matches(pattern.as_bytes(), file_name.as_bytes());
+    }
+    glob_matches(pattern.as_bytes(), path.as_bytes())
+}
+
+fn glob_matches(pattern: &[u8], text: &[u8]) -> bool {
+    if let Some(rest) = pattern.strip_prefix(b"**/") {
+        // Zero or more leading directories.
+        return glob_matches(rest, text)
+            || text
+                .iter()
+                .enumerate()
+                .any(|(i, c)| *c == b'/' && glob_matches(rest, &text[i + 1..]));
+    }
+    if pattern == b"/**" || pattern == b"**" {
+        return !text.is_empty();
+    }
+    match (pattern.first(), text.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            let rest = &pattern[1..];
+            if glob_matches(rest, text) {
+                return true;
+            }
+            match text.first() {
+                Some(b'/') | None => false,
+                Some(_) => glob_matches(pattern, &text[1..]),
+            }
+        }
+        (Some(b'?'), Some(c)) if *c != b'/' => glob_matches(&pattern[1..], &text[1..]),
+        (Some(p), Some(c)) if p == c => glob_matches(&pattern[1..], &text[1..]),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_patterns() {
+        let cases = [
+            ("*.rs", "src/main.rs", true),
+            ("*.rs", "main.rs", true),
+            ("*.rs", "main.rsx", false),
+            ("docs/*", "docs/index.html", true),
+            ("docs/*", "docs/api/index.html", false),
+            ("docs/**", "docs/api/index.html", true),
+            ("/vendor/**", "vendor/lib.c", true),
+            ("vendor/**", "src/vendor/lib.c", false),
+            ("**/generated/*.rs", "src/generated/queries.rs", true),
+            ("**/generated/*.rs", "generated/queries.rs", true),
+            ("src/?.c", "src/a.c", true),
+            ("src/?.c", "src/ab.c", false),
+        ];
+        for (pattern, path, expected) in cases {
+            assert_eq!(pattern_matches(pattern, path), expected, "{pattern} vs {path}");
+        }
+    }
+
+    #[test]
+    fn gitattributes_later_rules_win() {
+        let attributes = GitAttributes {
+            rules: parse_gitattributes(
+                "",
+                "# comment\n\
+                 third_party/** linguist-vendored\n\
+                 third_party/ours/** -linguist-vendored\n\
+                 *.inc linguist-language=C++\n\
+                 src/github_queries.rs linguist-generated=true\n",
+            ),
+        };
+        assert_eq!(attributes.for_path("third_party/lib/x.c").vendored, Some(true));
+        assert_eq!(attributes.for_path("third_party/ours/x.c").vendored, Some(false));
+        assert_eq!(attributes.for_path("src/main.rs"), Attributes::default());
+        assert_eq!(attributes.for_path("src/github_queries.rs").generated, Some(true));
+        assert_eq!(attributes.for_path("src/x.inc").language.as_deref(), Some("C++"));
+    }
+
+    #[test]
+    fn nested_gitattributes_are_relative() {
+        let attributes = GitAttributes {
+            rules: parse_gitattributes("web/", "/dist/** linguist-generated\n"),
+        };
+        assert_eq!(attributes.for_path("web/dist/app.js").generated, Some(true));
+        assert_eq!(attributes.for_path("dist/app.js").generated, None);
+    }
+
+    #[test]
+    fn shebang_interpreters() {
+        assert_eq!(interpreter_from_shebang("#!/usr/bin/env python3").as_deref(), Some("python"));
+        assert_eq!(interpreter_from_shebang("#!/bin/bash -e").as_deref(), Some("bash"));
+        assert_eq!(interpreter_from_shebang("#!/usr/bin/env -S perl -w").as_deref(), Some("perl"));
+        assert_eq!(interpreter_from_shebang("#!/usr/bin/python3.11").as_deref(), Some("python"));
+        assert_eq!(interpreter_from_shebang("fn main() {}"), None);
+    }
+
+    #[test]
+    fn blame_attribution() {
+        let porcelain = b"1111111111111111111111111111111111111111 1 1 2\n\
+author Anti\n\
+author-mail <12345+AndreasOM@users.noreply.github.com>\n\
+filename src/main.rs\n\
+\tfn main() {\n\
+2222222222222222222222222222222222222222 2 2 1\n\
+author Someone Else\n\
+author-mail <someone@example.com>\n\
+filename src/main.rs\n\
+\t    println!(\"hi\");\n\
+1111111111111111111111111111111111111111 3 3\n\
+author Anti\n\
+author-mail <12345+AndreasOM@users.noreply.github.com>\n\
+filename src/main.rs\n\
+\t}\n";
+        let emails = ["andreasom@users.noreply.github.com"];
+        assert_eq!(sum_authored_bytes(porcelain, &emails), "fn main() {\n}\n".len() as i64);
+        assert_eq!(sum_authored_bytes(porcelain, &["nobody@example.com"]), 0);
+    }
+
+    #[test]
+    fn files_that_cant_be_blamed_are_skipped() {
+        let tmp = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            let status = Command::new("git").arg("-C").arg(tmp.path()).args(args).status().unwrap();
+            assert!(status.success(), "git {args:?}");
+        };
+        git(&["init", "--quiet"]);
+        fs::write(tmp.path().join("main.rs"), "fn main() {}\n").unwrap();
+        git(&["add", "main.rs"]);
+        git(&["-c", "user.name=Anti", "-c", "user.email=anti@example.com", "commit", "--quiet", "-m", "Init"]);
+        // Tracked, but not in HEAD, so blaming it fails.
+        fs::write(tmp.path().join("lib.rs"), "pub fn lib() {}\n").unwrap();
+        git(&["add", "lib.rs"]);
+
+        let counting = Counting::AuthoredBy(vec!["anti@example.com".to_string()]);
+        let languages = languages_in_checkout(tmp.path(), &counting).unwrap();
+
+        assert_eq!(languages.len(), 1);
+        assert_eq!(languages[0].name, "Rust");
+        assert_eq!(languages[0].bytes, "fn main() {}\n".len() as i64);
+    }
+
+    #[test]
+    fn languages_are_known_to_linguist() {
+        for language in LANGUAGES {
+            let info = linguist::lookup(language.name);
+            assert_eq!(info.map(|l| l.name), Some(language.name));
+            assert!(info.and_then(|l| l.color).is_some(), "{} has no color", language.name);
+        }
+    }
+
+    #[test]
+    fn classification_by_path() {
+        assert_eq!(language_by_path("src/main.rs").map(|l| l.name), Some("Rust"));
+        assert_eq!(language_by_path("Makefile").map(|l| l.name), Some("Makefile"));
+        assert_eq!(language_by_path("src/shader.FRAG").map(|l| l.name), Some("GLSL"));
+        assert_eq!(language_by_path("README.md").map(|l| l.name), None);
+        assert!(is_vendored_by_default("vendor/lib.c"));
+        assert!(!is_vendored_by_default("src/vendor.rs"));
+    }
+}
@@ -1,95 +1,109 @@
-// graphql-client generate --schema-path ./graphql/github.schema.graphql --custom-scalars-module crate::gql_types --output-directory ./src/ --response-derives Debug ./graphql/github_queries.graphql
-mod github_queries;
-pub(crate) mod gql_types {
-    #[allow(clippy::upper_case_acronyms)]
-    pub(crate) type URI = String;
-    pub(crate) type DateTime = String; //chrono::DateTime<chrono::Utc>;
-}
-
 use anyhow::Result;
-use chrono::{DateTime, Datelike, Utc};
-use github_queries::{
-    issues_and_prs_query, user_repos_query, IssuesAndPrsQuery, UserReposQuery,
+use clap::Parser;
+use generator::{
+    aggregate::FeaturedConfig, check, commit, html, local_languages::Counting, output, sample, schema, snapshot::Snapshot, svg, Client, Context,
+    LanguageGroupTotals, Sources,
+};
+use regex::Regex;
+use std::{
+    env,
+    path::{Path, PathBuf},
 };
-use human_bytes::human_bytes;
-use itertools::Itertools;
-use once_cell::sync::Lazy;
-use reqwest::Client;
-use serde::Serialize;
-use serde_json;
-use std::{cmp::Ordering, collections::HashMap, env, fs::File, io::Write, path::PathBuf};
-use tinytemplate::TinyTemplate;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 const MY_LOGIN: &str = "AndreasOM";
+// The addresses I commit with, used to attribute lines with `git blame` when
+// LOCAL_LANGUAGE_MODE=authored.
+const MY_EMAILS: &[&str] = &[
+    "AndreasOM@users.noreply.github.com",
+];
 
-// Repository listing configuration
-const TOP_STARRED_REPOS: usize = 5;
-const TOP_FORKED_REPOS: usize = 5;
-const TOP_RECENT_REPOS: usize = 10;
-
-// Language statistics configuration
-const MIN_LANGUAGE_PERCENTAGE: f64 = 1.0;
-
-// API rate limiting configuration
-const PAGINATION_DELAY_MS: u64 = 200;
-
-#[derive(Debug, Serialize)]
-struct MyRepo {
-    full_name: String,
-    url: String,
-    fork_count: i64,
-    stargazer_count: i64,
-    pushed_date: String,
-}
-
-#[derive(Debug, Default, Serialize)]
-struct UserAndRepoStats {
-    created_at: String,
-    total_repos: i64,
-    owned_repos: i64,
-    forked_repos: i64,
-    live_repos: i64,
-    all_time_languages: HashMap<String, (String, i64)>,
-    recent_languages: HashMap<String, (String, i64)>,
-    repos: Vec<MyRepo>,
-}
-
-#[derive(Debug, Serialize)]
-struct TopRepos<'a> {
-    most_recent: Vec<&'a MyRepo>,
-    most_starred: Vec<&'a MyRepo>,
-    most_forked: Vec<&'a MyRepo>,
-}
-
-#[derive(Debug, Serialize)]
-struct LanguageStat<'a> {
-    name: &'a str,
-    color: &'a str,
-    percentage: i64,
-    bytes: String,
-}
+// Commit authors (login, name or email) that are bots, on top of the usual
+// ones like Dependabot and Renovate. Their commits don't make a repo recent.
+const BOT_AUTHORS: &[&str] = &[
+//    r"^murderbot@example\.com$",
+];
 
-#[derive(Debug, Default, Serialize)]
-struct IssueAndPrStats {
-    issues_created: i64,
-    issues_closed: i64,
-    prs_created: i64,
-    prs_merged: i64,
-}
+// The repos to show off in the README, in this order, grouped by category.
+const FEATURED_REPOS: &[FeaturedConfig] = &[
+    FeaturedConfig {
+        full_name: "AndreasOM/fiiish-v3",
+        category: "Games",
+        blurb: "The third take on Fiiish!",
+    },
+    FeaturedConfig {
+        full_name: "AndreasOM/omt",
+        category: "Tools",
+        blurb: "The tools behind my game asset pipelines",
+    },
+    FeaturedConfig {
+        full_name: "AndreasOM/shader-crusher",
+        category: "Tools",
+        blurb: "Shrinks GLSL shaders",
+    },
+];
 
-#[derive(Serialize)]
-struct Context<'a> {
-    user_and_repo_stats: &'a UserAndRepoStats,
-    top_repos: TopRepos<'a>,
-    issue_and_pr_stats: IssueAndPrStats,
-    top_all_time_languages: Vec<LanguageStat<'a>>,
-    top_recent_languages: Vec<LanguageStat<'a>>,
+// The star history and language evolution charts, next to README.md.
+const STAR_HISTORY_SVG: &str = "star-history.svg";
+const LANGUAGE_EVOLUTION_SVG: &str = "language-evolution.svg";
+
+// The exit code when some data sources failed, but the README was still
+// written with the sections that did succeed.
+const DEGRADED_EXIT_CODE: i32 = 2;
+// The exit code of --check when README.md doesn't match the generated one.
+const DRIFT_EXIT_CODE: i32 = 3;
+
+// Commit configuration for --commit. The message template can use
+// `{summary}` (e.g. "stars +3, 2 new repos") and `{changes.stars}`,
+// `{changes.forks}` and `{changes.repos}`.
+const COMMIT_AUTHOR: &str = "Murderbot <murderbot@example.com>";
+const COMMIT_MESSAGE_TEMPLATE: &str = "Regenerated profile README.md{{ if summary }} ({summary}){{ endif }}";
+
+/// Generates the profile README.md from the repos and activity on GitHub and
+/// the other configured forges.
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Args {
+    /// Don't write README.md, but check that it matches what would be
+    /// generated. Prints a diff and exits with 3 if it doesn't.
+    #[arg(long)]
+    check: bool,
+    /// With --check, ignore differences in dates, relative dates, ages and
+    /// the stars gained recently.
+    #[arg(long, requires = "check")]
+    ignore_dates: bool,
+    /// With --check, ignore differences in text matching this regex. Can be
+    /// given multiple times.
+    #[arg(long = "ignore", value_name = "REGEX", requires = "check")]
+    ignore_patterns: Vec<Regex>,
+    /// Also write a standalone HTML profile page to this path.
+    #[arg(long, value_name = "PATH", conflicts_with = "check")]
+    html: Option<PathBuf>,
+    /// Commit README.md (and the HTML page) if it changed.
+    #[arg(long, conflicts_with = "check")]
+    commit: bool,
+    /// Push after committing.
+    #[arg(long, requires = "commit")]
+    push: bool,
+    /// The author of the commit, as `Name <email>`.
+    #[arg(long, value_name = "AUTHOR", default_value = COMMIT_AUTHOR)]
+    commit_author: commit::Author,
+    /// The template for the commit message.
+    #[arg(long, value_name = "TEMPLATE", default_value = COMMIT_MESSAGE_TEMPLATE)]
+    commit_message: String,
+    /// Print the fields templates can use, with their types, and exit.
+    #[arg(long, conflicts_with_all = ["check", "html", "commit"])]
+    schema: bool,
+    /// Render the README from made-up sample data to stdout, without
+    /// fetching anything, and exit.
+    #[arg(long, conflicts_with_all = ["check", "html", "commit", "schema"])]
+    sample: bool,
+    /// Print the sample context as JSON, as templates see it, and exit.
+    #[arg(long, conflicts_with_all = ["check", "html", "commit", "schema", "sample"])]
+    sample_context: bool,
 }
 
-const README_TEMPLATE: &str = include_str!("../README_TEMPLATE.md");
-
 /*
 ## GitHub Activity Stats
 - {issue_and_pr_stats.prs_created} PRs created
@@ -98,438 +112,185 @@ const README_TEMPLATE: &str = include_str!("../README_TEMPLATE.md");
   - of which {issue_and_pr_stats.issues_closed} have been closed
 */
 
-const API_URL: &str = "https://api.github.com/graphql";
-
 #[tokio::main]
 async fn main() -> Result<()> {
+    let args = Args::parse();
     tracing_subscriber::fmt::init();
 
-    let token = env::var("GITHUB_TOKEN")
-        .expect("You must set the GITHUB_TOKEN env var when running this program");
-    let client = Client::builder()
-        .user_agent(format!("andreasOM-profiler-generator/{}", VERSION))
-        .default_headers(
-            std::iter::once((
-                reqwest::header::AUTHORIZATION,
-                reqwest::header::HeaderValue::from_str(&format!("Bearer {}", token))
-                    .map_err(|e| anyhow::anyhow!("Invalid authorization header: {}", e))?,
-            ))
-            .collect(),
-        )
-        .build()?;
-
-    let user_and_repo_stats = user_and_repo_stats(&client).await?;
-    tracing::debug!("{user_and_repo_stats:#?}");
-    let top_repos = top_repos(&user_and_repo_stats.repos);
-    let top_all_time_languages = top_languages(&user_and_repo_stats.all_time_languages);
-    tracing::debug!("{top_all_time_languages:#?}");
-    let top_recent_languages = top_languages(&user_and_repo_stats.recent_languages);
-    tracing::debug!("{top_recent_languages:#?}");
-    let issue_and_pr_stats = issue_and_pr_stats(&client).await?;
-    tracing::debug!("{issue_and_pr_stats:#?}");
-
-    let mut tt = TinyTemplate::new();
-    tt.add_template("readme", README_TEMPLATE)?;
-    let context = Context {
-        user_and_repo_stats: &user_and_repo_stats,
-        top_repos,
-        issue_and_pr_stats,
-        top_all_time_languages,
-        top_recent_languages,
-    };
-
-    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
-    path.push("README.md");
-    let mut file = File::create(path)?;
-    file.write_all(tt.render("readme", &context)?.as_bytes())?;
-
-    Ok(())
-}
-
-async fn graphql_with_retry<Q: graphql_client::GraphQLQuery>(
-    client: &Client,
-    url: &str,
-    variables: Q::Variables,
-) -> Result<graphql_client::Response<Q::ResponseData>>
-where
-    Q::Variables: serde::Serialize + Clone,
-{
-    const MAX_RETRIES: u32 = 4;
-    const INITIAL_DELAY_MS: u64 = 1000;
-
-    for attempt in 1..=MAX_RETRIES {
-        tracing::debug!("GraphQL request attempt {}/{}", attempt, MAX_RETRIES);
-
-        // Build the request body
-        let request_body = graphql_client::QueryBody {
-            variables: variables.clone(),
-            query: Q::build_query(variables.clone()).query,
-            operation_name: Q::build_query(variables.clone()).operation_name,
-        };
-
-        // Make the HTTP request directly to capture the raw response
-        let http_response = client
-            .post(url)
-            .json(&request_body)
-            .send()
-            .await;
-
-        match http_response {
-            Ok(response) => {
-                let status = response.status();
-                tracing::debug!("HTTP response status: {}", status);
-
-                // Get the raw response text
-                let response_text = match response.text().await {
-                    Ok(text) => text,
-                    Err(e) => {
-                        tracing::error!("Failed to read response body: {}", e);
-                        if attempt < MAX_RETRIES {
-                            let delay_ms = INITIAL_DELAY_MS * 2u64.pow(attempt - 1);
-                            tracing::warn!("Retrying in {}ms...", delay_ms);
-                            tokio::time::sleep(tokio::time::Duration::from_millis(delay_ms)).await;
-                            continue;
-                        } else {
-                            return Err(anyhow::anyhow!("Failed to read response body: {}", e));
-                        }
-                    }
-                };
-
-                // Try to parse as GraphQL response
-                match serde_json::from_str::<graphql_client::Response<Q::ResponseData>>(&response_text) {
-                    Ok(graphql_response) => {
-                        tracing::debug!("GraphQL request succeeded on attempt {}", attempt);
-                        return Ok(graphql_response);
-                    }
-                    Err(e) => {
-                        tracing::error!("GraphQL request attempt {}/{} failed to parse response", attempt, MAX_RETRIES);
-                        tracing::error!("Parse error: {}", e);
-                        tracing::error!("HTTP status: {}", status);
-                        tracing::error!("Full response body:\n{}", response_text);
-
-                        if attempt < MAX_RETRIES {
-                            let delay_ms = INITIAL_DELAY_MS * 2u64.pow(attempt - 1);
-                            tracing::warn!("Retrying in {}ms...", delay_ms);
-                            tokio::time::sleep(tokio::time::Duration::from_millis(delay_ms)).await;
-                        } else {
-                            tracing::error!("All {} retry attempts exhausted", MAX_RETRIES);
-                            return Err(anyhow::anyhow!(
-                                "GraphQL request failed after {} attempts. Last error: {}. HTTP status: {}",
-                                MAX_RETRIES,
-                                e,
-                                status
-                            ));
-                        }
-                    }
-                }
-            }
-            Err(e) => {
-                tracing::error!("HTTP request attempt {}/{} failed: {}", attempt, MAX_RETRIES, e);
-
-                if attempt < MAX_RETRIES {
-                    let delay_ms = INITIAL_DELAY_MS * 2u64.pow(attempt - 1);
-                    tracing::warn!("Retrying in {}ms...", delay_ms);
-                    tokio::time::sleep(tokio::time::Duration::from_millis(delay_ms)).await;
-                } else {
-                    tracing::error!("All {} retry attempts exhausted", MAX_RETRIES);
-                    return Err(anyhow::anyhow!("HTTP request failed after {} attempts: {}", MAX_RETRIES, e));
-                }
-            }
-        }
+    if args.schema {
+        print!("{}", schema::tree());
+        return Ok(());
     }
-
-    unreachable!("Loop should always return before reaching here");
-}
-
-async fn user_and_repo_stats(client: &Client) -> Result<UserAndRepoStats> {
-    let mut stats = UserAndRepoStats::default();
-    let mut after = None;
-    tracing::info!("Getting user repos for login: {}", MY_LOGIN);
-    loop {
-        let vars = user_repos_query::Variables {
-            login: MY_LOGIN.to_string(),
-            after,
-        };
-        tracing::debug!("Making GraphQL request to {} for user {}", API_URL, MY_LOGIN);
-        let resp = graphql_with_retry::<UserReposQuery>(client, API_URL, vars).await?;
-        tracing::debug!("{resp:#?}");
-
-        if resp.data.is_none() {
-            tracing::error!("No data in GraphQL response. Full response: {:#?}", resp);
-        }
-        let data = resp.data.ok_or_else(|| anyhow::anyhow!("No data in GraphQL response"))?;
-
-        if data.user.is_none() {
-            tracing::error!("No user in GraphQL response");
-        }
-        let user = data.user.ok_or_else(|| anyhow::anyhow!("No user in GraphQL response"))?;
-
-        if stats.created_at.is_empty() {
-            stats.created_at = user.created_at;
-        }
-
-        let nodes = user.repositories
-                .nodes
-                .ok_or_else(|| anyhow::anyhow!("No repository nodes in response"))?;
-
-        let owned_repos: Vec<_> = nodes
-                .into_iter()
-                .filter_map(|r| r)
-                .filter(|r| r.owner.login == MY_LOGIN)
-                .collect();
-
-        collect_user_repo_stats(&mut stats, owned_repos)?;
-
-        if user.repositories.page_info.has_next_page {
-            after = user.repositories.page_info.end_cursor;
-            // Small delay between paginated requests to avoid rate limiting
-            tokio::time::sleep(tokio::time::Duration::from_millis(PAGINATION_DELAY_MS)).await;
+    if args.sample || args.sample_context {
+        let user_and_repo_stats = sample::user_and_repo_stats();
+        let language_groups = LanguageGroupTotals::new(&user_and_repo_stats);
+        let issue_and_pr_stats = sample::issue_and_pr_stats();
+        let star_history = sample::star_history();
+        let context = Context::new(
+            Some(&user_and_repo_stats),
+            Some(&language_groups),
+            sample::featured(),
+            Some(&issue_and_pr_stats),
+            Some(&star_history),
+            Sources::default(),
+            sample::now(),
+        );
+        if args.sample_context {
+            println!("{}", serde_json::to_string_pretty(&generator::readme_context_json(&context)?)?);
         } else {
-            break;
+            print!("{}", render_readme(&context)?);
         }
+        return Ok(());
     }
-    Ok(stats)
-}
-
-fn collect_user_repo_stats(
-    stats: &mut UserAndRepoStats,
-    repos: Vec<user_repos_query::ReposNodes>,
-) -> Result<()> {
-    for repo in repos {
-        if repo.is_archived || repo.is_disabled || repo.is_empty || repo.is_private {
-            continue;
-        }
-
-        stats.total_repos += 1;
-        if repo.is_fork {
-            stats.forked_repos += 1;
-            continue;
-        }
-
-        stats.owned_repos += 1;
-
-        let languages = match repo.languages.as_ref() {
-            Some(langs) => langs,
-            None => continue, // Skip repos with no language data
-        };
-
-        let lang_sizes: Vec<_> = languages
-            .edges
-            .as_ref()
-            .map(|edges| {
-                edges.iter()
-                    .filter_map(|e| e.as_ref().map(|edge| edge.size))
-                    .collect()
-            })
-            .unwrap_or_default();
-
-        let lang_names_and_colors: Vec<_> = languages
-            .nodes
-            .as_ref()
-            .map(|nodes| {
-                nodes.iter()
-                    .filter_map(|l| l.as_ref().map(|lang| (lang.name.as_str(), lang.color.as_deref())))
-                    .collect()
-            })
-            .unwrap_or_default();
-
-        collect_language_stats(
-            &mut stats.all_time_languages,
-            repo.name_with_owner.as_str(),
-            &lang_sizes,
-            &lang_names_and_colors,
-        );
 
-        let pushed_date = match repo.pushed_at.as_ref() {
-            Some(d) => d,
-            None => continue, // Skip repos without pushed_at
+    let token = env::var("GITHUB_TOKEN")
+        .expect("You must set the GITHUB_TOKEN env var when running this program");
+    let user_agent = format!("andreasOM-profiler-generator/{}", VERSION);
+    let mut client = Client::builder(user_agent)
+        .github(MY_LOGIN, &token)?
+        .extra_forges_from_env()?
+        .bot_authors(BOT_AUTHORS)?;
+    if let Ok(dir) = env::var("LOCAL_CHECKOUTS_DIR") {
+        let counting = match env::var("LOCAL_LANGUAGE_MODE").as_deref() {
+            Ok("authored") => Counting::AuthoredBy(MY_EMAILS.iter().map(|e| e.to_string()).collect()),
+            Ok("bytes") | Err(_) => Counting::AllBytes,
+            Ok(other) => {
+                return Err(anyhow::anyhow!(
+                    "Unknown LOCAL_LANGUAGE_MODE `{other}`; expected `bytes` or `authored`"
+                ))
+            }
         };
-
-        let pushed_date = DateTime::parse_from_rfc3339(pushed_date)?.with_timezone(&Utc);
-        if pushed_date < *FILTER_DATE {
-            continue;
-        }
-
-        collect_language_stats(
-            &mut stats.recent_languages,
-            repo.name_with_owner.as_str(),
-            &lang_sizes,
-            &lang_names_and_colors,
-        );
-
-        stats.live_repos += 1;
-
-        stats.repos.push(MyRepo {
-            full_name: repo.name_with_owner,
-            url: repo.url,
-            fork_count: repo.fork_count,
-            stargazer_count: repo.stargazer_count,
-            pushed_date: pushed_date.format("%Y-%m-%d").to_string(),
-        });
+        client = client.local_checkouts(dir, counting);
     }
+    if let Ok(weighting) = env::var("LANGUAGE_WEIGHTING") {
+        client = client.language_weighting(weighting.parse()?);
+    }
+    let client = client.build();
 
-    Ok(())
-}
-
-static FILTER_DATE: Lazy<DateTime<Utc>> = Lazy::new(|| {
+    // The data sources are independent, so they are fetched concurrently. We
+    // wait for all of them before bailing out on the first error, so one
+    // failing source doesn't cancel the others mid-request.
+    // One clock for the whole run, so what counts as recent matches the dates
+    // the README shows.
     let now = chrono::Utc::now();
-    // The chrono::Duration struct cannot represent 2 years, only multiple of
-    // weeks, but two years is not 104 weeks.  let two_years_ago =
-    let two_years_ago = format!("{}-{}", now.year() - 2, now.format("%m-%dT%H:%M:%SZ"),);
-    chrono::DateTime::parse_from_rfc3339(&two_years_ago)
-        .unwrap_or_else(|_| panic!("Could not parse `{two_years_ago}` as an RFC3339 date"))
-        .with_timezone(&Utc)
-});
+    let (user_and_repo_stats, issue_and_pr_stats, star_history) = tokio::join!(
+        client.user_and_repo_stats(now),
+        client.issue_and_pr_stats(),
+        client.star_history()
+    );
+    // With SNAPSHOT_PATH set, the data of each source is saved after a
+    // successful fetch, and used for its sections when fetching fails.
+    let snapshot_path = env::var("SNAPSHOT_PATH").ok().map(PathBuf::from);
+    let mut snapshot = snapshot_path.as_deref().map(Snapshot::load).unwrap_or_default();
+    // Compare with the previous run for the commit message, before the
+    // snapshot is updated.
+    let changes = match (&user_and_repo_stats, &snapshot.user_and_repo_stats) {
+        (Ok(stats), Some(previous)) => Some(commit::ChangeSummary::between(&previous.data, stats)),
+        _ => None,
+    };
+    let sources = Sources {
+        user_and_repo_stats: generator::with_fallback(
+            "user and repo stats",
+            user_and_repo_stats,
+            &mut snapshot.user_and_repo_stats,
+        ),
+        issue_and_pr_stats: generator::with_fallback(
+            "issue and PR stats",
+            issue_and_pr_stats,
+            &mut snapshot.issue_and_pr_stats,
+        ),
+        star_history: generator::with_fallback("star history", star_history, &mut snapshot.star_history),
+    };
+    let degraded = sources.degraded();
+    // --check must not write anything, so the snapshot isn't updated either.
+    if let Some(path) = snapshot_path.as_ref().filter(|_| !args.check) {
+        snapshot.save(path)?;
+    }
 
-const REPOS_TO_IGNORE_FOR_LANGUAGE_STATS: &[&str] = &[
-    // The presentations repo has a ton of HTML and JS I didn't write
-    // and this distorts the stats.
-//    "autarch/presentations",
-    // The mason book is HTML, but it's just the HTMl from the old dynamic
-    // site which I crawled, so it's not interesting for these stats.
-//    "autarch/masonbook.houseabsolute.com",
-];
+    let user_and_repo_stats = snapshot.user_and_repo_stats.as_ref().map(|s| &s.data);
+    tracing::debug!("{user_and_repo_stats:#?}");
+    let issue_and_pr_stats = snapshot.issue_and_pr_stats.as_ref().map(|s| &s.data);
+    tracing::debug!("{issue_and_pr_stats:#?}");
+    let star_history = snapshot.star_history.as_ref().map(|s| &s.data);
 
-fn collect_language_stats(
-    stats: &mut HashMap<String, (String, i64)>,
-    repo_name: &str,
-    lang_sizes: &[i64],
-    lang_names_and_colors: &[(&str, Option<&str>)],
-) {
-    if lang_sizes.len() != lang_names_and_colors.len() {
-        tracing::warn!(
-            "language sizes and names differ in length: {} != {} for {}; skipping",
-            lang_sizes.len(),
-            lang_names_and_colors.len(),
-            repo_name,
-        );
-        return;
-    }
-    if !lang_sizes.is_empty() && !REPOS_TO_IGNORE_FOR_LANGUAGE_STATS.contains(&repo_name) {
-        for i in 0..lang_sizes.len() {
-            let lang = match (repo_name, lang_names_and_colors[i].0) {
-                // This is really XS, not C (although arguably, XS is just C).
-                //("houseabsolute/File-LibMagic", "C") => "XS",
-                (_, l) => l,
-            };
+    let language_groups = user_and_repo_stats.map(LanguageGroupTotals::new);
+    let context = Context::new(
+        user_and_repo_stats,
+        language_groups.as_ref(),
+        FEATURED_REPOS,
+        issue_and_pr_stats,
+        star_history,
+        sources,
+        now,
+    );
 
-            // The tidyall repo has a bunch of PHP and JS checked in for
-            // testing, but none of it is code I've written or maintained.
-            /*
-            if repo_name == "houseabsolute/perl-code-tidyall" && lang != "Perl" {
-                continue;
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("README.md");
+    let rendered = render_readme(&context)?;
+    if args.check {
+        // Comparing against fallbacks would only report noise.
+        if degraded {
+            tracing::error!("Some data sources failed; cannot check {} for drift", path.display());
+            std::process::exit(DEGRADED_EXIT_CODE);
+        }
+        let mut ignore = args.ignore_patterns;
+        if args.ignore_dates {
+            ignore.extend(check::date_patterns());
+        }
+        match check::drift(&path, &rendered, &ignore)? {
+            Some(diff) => {
+                print!("{diff}");
+                tracing::error!("{} is out of date", path.display());
+                std::process::exit(DRIFT_EXIT_CODE);
             }
-            */
-            let color = language_color(lang, lang_names_and_colors[i].1);
-            let size = lang_sizes[i];
-            if let Some(v) = stats.get_mut(lang) {
-                (*v).1 += size;
-            } else {
-                stats.insert(lang.to_string(), (color.to_string(), size));
+            None => {
+                tracing::info!("{} is up to date", path.display());
+                return Ok(());
             }
         }
     }
-}
-
-fn language_color<'a, 'b>(lang: &'a str, color: Option<&'b str>) -> &'b str {
-    match color {
-        Some(c) => c,
-        None => match lang {
-            "Perl 6" => "#00A9E0",
-            "XS" => "#021c9e", // a darker blue than Perl,
-            _ => {
-                tracing::warn!("No color defined for language '{}'; using default gray", lang);
-                "#808080" // Default gray color
-            }
-        },
+    output::write_if_changed(&path, rendered.as_bytes())?;
+    let mut outputs = vec![path];
+    if let Some(growth) = &context.star_history {
+        let svg_path = Path::new(env!("CARGO_MANIFEST_DIR")).join(STAR_HISTORY_SVG);
+        output::write_if_changed(&svg_path, svg::star_history(growth, context.now.naive_utc().date()).as_bytes())?;
+        outputs.push(svg_path);
     }
-}
-
-fn top_repos(repos: &[MyRepo]) -> TopRepos<'_> {
-    let most_forked = repos
-        .iter()
-        .filter(|r| r.fork_count > 0)
-        .sorted_by(|a, b| b.fork_count.cmp(&a.fork_count))
-        .take(TOP_FORKED_REPOS)
-        .collect::<Vec<_>>();
-    let most_starred = top_n(repos, TOP_STARRED_REPOS, |a, b| b.stargazer_count.cmp(&a.stargazer_count));
-    let most_recent = top_n(repos, TOP_RECENT_REPOS, |a, b| b.pushed_date.cmp(&a.pushed_date));
-    TopRepos {
-        most_forked,
-        most_recent,
-        most_starred,
+    if let Some(years) = context.language_evolution.as_deref().filter(|y| !y.is_empty()) {
+        let svg_path = Path::new(env!("CARGO_MANIFEST_DIR")).join(LANGUAGE_EVOLUTION_SVG);
+        output::write_if_changed(&svg_path, svg::language_evolution(years).as_bytes())?;
+        outputs.push(svg_path);
+    }
+    if let Some(html_path) = &args.html {
+        output::write_if_changed(html_path, html::render(&context, MY_LOGIN)?.as_bytes())?;
+        // git runs in the repo dir, so relative paths won't do.
+        outputs.push(html_path.canonicalize()?);
     }
-}
-
-fn top_n<S>(repos: &[MyRepo], take: usize, sorter: S) -> Vec<&MyRepo>
-where
-    S: FnMut(&&MyRepo, &&MyRepo) -> Ordering,
-{
-    repos
-        .iter()
-        .sorted_by(sorter)
-        .take(take)
-        .collect::<Vec<_>>()
-}
-
-fn top_languages(languages: &HashMap<String, (String, i64)>) -> Vec<LanguageStat<'_>> {
-    let total_size: i64 = languages.values().map(|v| v.1).sum();
-    let colors: HashMap<&str, &str> = languages
-        .iter()
-        .map(|(k, v)| (k.as_str(), v.0.as_str()))
-        .collect();
 
-    let mut language_sums: HashMap<&str, i64> = HashMap::new();
-    for (lang, (_, size)) in languages {
-        if let Some(v) = language_sums.get_mut(lang.as_str()) {
-            *v += *size;
-        } else {
-            language_sums.insert(lang, *size);
-        }
+    if args.commit {
+        let message = commit::commit_message(&args.commit_message, changes.as_ref())?;
+        commit::commit_if_changed(
+            Path::new(env!("CARGO_MANIFEST_DIR")),
+            &outputs.iter().map(PathBuf::as_path).collect::<Vec<_>>(),
+            &args.commit_author,
+            &message,
+            args.push,
+        )?;
     }
 
-    let mut top = vec![];
-    for (name, sum) in language_sums {
-        let pct = (sum as f64 / total_size as f64) * 100.0;
-        if pct < MIN_LANGUAGE_PERCENTAGE {
-            tracing::debug!("Skipping language {name} with total percentage of {pct}");
-            continue;
-        }
-        let color = colors.get(name).copied().unwrap_or_else(|| {
-            tracing::warn!("No color found for language '{}'; using default", name);
-            "#808080"
-        });
-        top.push(LanguageStat {
-            name,
-            color,
-            percentage: pct.round() as i64,
-            bytes: human_bytes(sum as f64),
-        })
+    if degraded {
+        tracing::error!("Some data sources failed; the README was written with fallbacks");
+        std::process::exit(DEGRADED_EXIT_CODE);
     }
 
-    top.sort_by(|a, b| b.percentage.cmp(&a.percentage));
-    top
+    Ok(())
 }
 
-async fn issue_and_pr_stats(client: &Client) -> Result<IssueAndPrStats> {
-    tracing::info!("Getting issue and pr data");
-    let resp = graphql_with_retry::<IssuesAndPrsQuery>(
-        client,
-        API_URL,
-        issues_and_prs_query::Variables {},
-    )
-    .await?;
-    tracing::debug!("{resp:#?}");
-
-    if resp.data.is_none() {
-        tracing::error!("No data in issues/PRs GraphQL response. Full response: {:#?}", resp);
+// README_TEMPLATE_PATH replaces the built-in template, e.g. with a `.jinja`
+// one when built with the `jinja` feature.
+fn render_readme(context: &Context<'_>) -> Result<String> {
+    match env::var("README_TEMPLATE_PATH") {
+        Ok(template) => generator::render_readme_from(Path::new(&template), context),
+        Err(_) => generator::render_readme(context),
     }
-    let data = resp.data.ok_or_else(|| anyhow::anyhow!("No data in issues/PRs GraphQL response"))?;
-    Ok(IssueAndPrStats {
-        issues_created: data.issues_created.issue_count,
-        issues_closed: data.issues_closed.issue_count,
-        prs_created: data.prs_created.issue_count,
-        prs_merged: data.prs_merged.issue_count,
-    })
 }
-
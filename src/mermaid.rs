@@ -0,0 +1,214 @@
+// Mermaid diagrams, which GitHub renders from ```mermaid blocks in Markdown.
+// Every chart comes as a whole fenced block, so templates can drop it in as
+// is.
+use itertools::Itertools;
+use serde::Serialize;
+use std::fmt::Write;
+
+use crate::{Context, IssueAndPrStats, LanguageStat, MonthlyPushes, RepoYear};
+
+// A pie chart only has this many theme colors, `pie1` to `pie12`.
+const MAX_PIE_COLORS: usize = 12;
+
+/// The charts for a README, built from the same `Context`.
+#[derive(Debug, Default, Serialize)]
+pub struct Charts {
+    pub recent_languages: Option<String>,
+    pub all_time_languages: Option<String>,
+    pub recent_language_groups: Option<String>,
+    pub all_time_language_groups: Option<String>,
+    pub push_activity: Option<String>,
+    pub repos_by_year: Option<String>,
+    pub issues_and_prs: Option<String>,
+}
+
+impl Charts {
+    pub fn new(context: &Context<'_>) -> Self {
+        let pie = |title, languages: &Option<Vec<LanguageStat<'_>>>| {
+            languages.as_deref().map(|l| language_pie(title, l))
+        };
+        Self {
+            recent_languages: pie("Past two years", &context.top_recent_languages),
+            all_time_languages: pie("All time", &context.top_all_time_languages),
+            recent_language_groups: pie("Past two years", &context.top_recent_language_groups),
+            all_time_language_groups: pie("All time", &context.top_all_time_language_groups),
+            push_activity: context.push_activity.as_deref().map(push_activity),
+            repos_by_year: context
+                .user_and_repo_stats
+                .filter(|s| !s.repos_by_year.is_empty())
+                .map(|s| repos_by_year(&s.repos_by_year)),
+            issues_and_prs: context.issue_and_pr_stats.map(issues_and_prs),
+        }
+    }
+}
+
+/// Escapes text for a quoted label. Mermaid has no backslash escapes, only
+/// entity codes like `#quot;`, so `#` itself needs one too.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '#' => escaped.push_str("#35;"),
+            '"' => escaped.push_str("#quot;"),
+            '<' => escaped.push_str("#lt;"),
+            '>' => escaped.push_str("#gt;"),
+            '\r' | '\n' => escaped.push(' '),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// A pie chart of the language percentages, in the languages' colors.
+pub fn language_pie(title: &str, languages: &[LanguageStat<'_>]) -> String {
+    // Mermaid sorts the slices by size before handing out the colors, so
+    // they have to be in that order here too.
+    let slices: Vec<_> = languages
+        .iter()
+        .filter(|l| l.percentage > 0)
+        .sorted_by_key(|l| std::cmp::Reverse(l.percentage))
+        .collect();
+    let colors = slices
+        .iter()
+        .take(MAX_PIE_COLORS)
+        .enumerate()
+        .filter(|(_, l)| is_hex_color(l.color))
+        .map(|(i, l)| format!(r#""pie{}": "{}""#, i + 1, l.color))
+        .join(", ");
+
+    let mut chart = String::from("```mermaid\n");
+    let _ = writeln!(chart, r#"%%{{init: {{"themeVariables": {{{colors}}}}}}}%%"#);
+    let _ = writeln!(chart, "pie title {}", escape(title));
+    for language in slices {
+        let _ = writeln!(chart, r#"    "{}" : {}"#, escape(language.name), language.percentage);
+    }
+    chart.push_str("```\n");
+    chart
+}
+
+/// How many repos were last pushed to in each month.
+pub fn push_activity(months: &[MonthlyPushes]) -> String {
+    let bars: Vec<_> = months.iter().map(|m| (m.month.as_str(), m.repos)).collect();
+    bar_chart("Repos by month of their last push", "Repos", &bars)
+}
+
+/// Bars for the repos created in each year, and a line for those last pushed
+/// to in it.
+pub fn repos_by_year(years: &[RepoYear]) -> String {
+    let labels = years.iter().map(|y| format!(r#""{}""#, y.year)).join(", ");
+    let mut chart = String::from("```mermaid\nxychart-beta\n");
+    let _ = writeln!(chart, r#"    title "Repos created (bars) and last pushed to (line) by year""#);
+    let _ = writeln!(chart, "    x-axis [{labels}]");
+    let _ = writeln!(chart, r#"    y-axis "Repos""#);
+    let _ = writeln!(chart, "    bar [{}]", years.iter().map(|y| y.created).join(", "));
+    let _ = writeln!(chart, "    line [{}]", years.iter().map(|y| y.pushed).join(", "));
+    chart.push_str("```\n");
+    chart
+}
+
+pub fn issues_and_prs(stats: &IssueAndPrStats) -> String {
+    bar_chart(
+        "Issues and pull requests",
+        "Count",
+        &[
+            ("PRs created", stats.prs_created),
+            ("PRs merged", stats.prs_merged),
+            ("Issues created", stats.issues_created),
+            ("Issues closed", stats.issues_closed),
+        ],
+    )
+}
+
+/// A bar chart with a bar for each `(label, value)`.
+pub fn bar_chart(title: &str, y_label: &str, bars: &[(&str, i64)]) -> String {
+    let labels = bars.iter().map(|(label, _)| format!(r#""{}""#, escape(label))).join(", ");
+    let values = bars.iter().map(|(_, value)| value).join(", ");
+    let mut chart = String::from("```mermaid\nxychart-beta\n");
+    let _ = writeln!(chart, r#"    title "{}""#, escape(title));
+    let _ = writeln!(chart, "    x-axis [{labels}]");
+    let _ = writeln!(chart, r#"    y-axis "{}""#, escape(y_label));
+    let _ = writeln!(chart, "    bar [{values}]");
+    chart.push_str("```\n");
+    chart
+}
+
+// The colors go into the init directive as they are, so anything but a plain
+// `#rrggbb` is left to the theme.
+fn is_hex_color(color: &str) -> bool {
+    color.len() == 7 && color.starts_with('#') && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn language<'a>(name: &'a str, color: &'a str, percentage: i64) -> LanguageStat<'a> {
+        LanguageStat {
+            name,
+            color,
+            percentage,
+            raw_percentage: percentage,
+            bytes: String::new(),
+            members: vec![],
+        }
+    }
+
+    #[test]
+    fn escaping() {
+        assert_eq!(escape(r#"C# "fast" <3"#), "C#35; #quot;fast#quot; #lt;3");
+        assert_eq!(escape("two\nlines"), "two lines");
+    }
+
+    #[test]
+    fn pie_uses_language_colors() {
+        let languages = [
+            language("Other", "#ededed", 30),
+            language("Rust", "#dea584", 50),
+            language("F#", "url(evil)", 20),
+            language("Lua", "#000080", 0),
+        ];
+        assert_eq!(
+            language_pie("Past two years", &languages),
+            r##"```mermaid
+%%{init: {"themeVariables": {"pie1": "#dea584", "pie2": "#ededed"}}}%%
+pie title Past two years
+    "Rust" : 50
+    "Other" : 30
+    "F#35;" : 20
+```
+"##
+        );
+    }
+
+    #[test]
+    fn repos_by_year_has_bars_and_a_line() {
+        let year = |year, created, pushed| RepoYear { year, created, pushed };
+        assert_eq!(
+            repos_by_year(&[year(2023, 2, 0), year(2024, 1, 3)]),
+            r#"```mermaid
+xychart-beta
+    title "Repos created (bars) and last pushed to (line) by year"
+    x-axis ["2023", "2024"]
+    y-axis "Repos"
+    bar [2, 1]
+    line [0, 3]
+```
+"#
+        );
+    }
+
+    #[test]
+    fn bar_chart_lists_labels_and_values() {
+        assert_eq!(
+            bar_chart("Repos by \"month\"", "Repos", &[("2024-05", 2), ("2024-06", 0)]),
+            r#"```mermaid
+xychart-beta
+    title "Repos by #quot;month#quot;"
+    x-axis ["2024-05", "2024-06"]
+    y-axis "Repos"
+    bar [2, 0]
+```
+"#
+        );
+    }
+}
@@ -0,0 +1,105 @@
+// Writes generated files so that a crash mid-write can never leave a
+// truncated file behind for the workflow to commit.
+use anyhow::Result;
+use std::{
+    fs::{self, File},
+    io::{ErrorKind, Write},
+    path::Path,
+};
+use tempfile::NamedTempFile;
+
+/// Writes `content` to `path` through a temp file in the same directory that
+/// is fsynced and then renamed into place. When the file already has exactly
+/// this content it is left alone, mtime included. Returns whether the file
+/// was written.
+pub fn write_if_changed(path: &Path, content: &[u8]) -> Result<bool> {
+    let existing = match fs::read(path) {
+        Ok(existing) => Some(existing),
+        Err(e) if e.kind() == ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+    if existing.as_deref() == Some(content) {
+        tracing::info!("{} is unchanged", path.display());
+        return Ok(false);
+    }
+
+    let dir = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    let mut temp = NamedTempFile::new_in(dir)?;
+    temp.write_all(content)?;
+    // Temp files are only readable by us, but the output should keep the
+    // permissions of the file it replaces.
+    match fs::metadata(path) {
+        Ok(metadata) => fs::set_permissions(temp.path(), metadata.permissions())?,
+        Err(_) => set_default_permissions(temp.path())?,
+    }
+    temp.as_file().sync_all()?;
+    temp.persist(path)?;
+    // Make the rename itself durable.
+    #[cfg(unix)]
+    File::open(dir)?.sync_all()?;
+
+    tracing::info!("Wrote {}", path.display());
+    Ok(true)
+}
+
+#[cfg(unix)]
+fn set_default_permissions(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o644))?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_default_permissions(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_new_and_changed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("README.md");
+
+        assert!(write_if_changed(&path, b"# One\n").unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "# One\n");
+
+        assert!(write_if_changed(&path, b"# Two\n").unwrap());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "# Two\n");
+
+        // No temp files are left behind.
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn identical_content_is_left_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("README.md");
+        fs::write(&path, "# Same\n").unwrap();
+        let old_mtime = fs::metadata(&path).unwrap().modified().unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+
+        assert!(!write_if_changed(&path, b"# Same\n").unwrap());
+        assert_eq!(fs::metadata(&path).unwrap().modified().unwrap(), old_mtime);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn permissions_are_kept() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("README.md");
+        assert!(write_if_changed(&path, b"# New\n").unwrap());
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o644);
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o664)).unwrap();
+        assert!(write_if_changed(&path, b"# Changed\n").unwrap());
+        assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o664);
+    }
+}
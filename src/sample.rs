@@ -0,0 +1,190 @@
+// Made-up but plausible stats, for developing and snapshot-testing templates
+// without a token. The repos come from a fixed seed and go through the same
+// aggregation as fetched ones, so the same sample always renders the same
+// README.
+use chrono::{DateTime, Duration, Utc};
+
+use crate::{
+    aggregate::{self, FeaturedConfig},
+    forge::{Language, Repo},
+    weighting::Weighting,
+    IssueAndPrStats, RepoStars, StarHistory, UserAndRepoStats,
+};
+
+// What the sample is relative to, so the dates in it don't move.
+const SAMPLE_NOW: &str = "2024-06-15T12:00:00Z";
+const SAMPLE_CREATED_AT: &str = "2012-03-04T09:30:00Z";
+const SAMPLE_OWNER: &str = "octocat";
+const SAMPLE_SEED: u64 = 1;
+// How far back the stars of the sample repos go.
+const SAMPLE_STAR_DAYS: u64 = 2500;
+
+// The repos' names and descriptions. Some of the descriptions are long or
+// messy on purpose, to show how they are cleaned up.
+const REPOS: &[(&str, &str)] = &[
+    ("pixel-forge", "A tiny pixel art editor"),
+    ("tiny_tool", ""),
+    ("dotfiles", "My *nix config"),
+    ("raytracer", "Weekend raytracer, following along with the books"),
+    ("game-jam-2023", "Our entry for the 2023 game jam:\n\"Out of [Control]\""),
+    ("asset-packer", "Packs textures, sounds and levels into one archive that the engine can load without unpacking anything at runtime"),
+    ("lua-bindings", "Lua bindings for the engine"),
+    ("homepage", "Sources of my homepage"),
+    ("shader-playground", "Live-reloading GLSL shaders | WIP"),
+    ("advent-of-code", "Solutions, mostly in Rust"),
+    ("midi-sequencer", "A step sequencer for MIDI gear"),
+    ("physics-sandbox", "Rigid bodies & constraints, for fun"),
+    ("old-engine", "The engine before the current one"),
+    ("build-scripts", "Shared CI and build scripts"),
+    ("tilemap-editor", "An editor for tile maps, with auto-tiling"),
+    ("notes", "Notes to self"),
+];
+
+// The repos to show off, as in the config.
+const FEATURED: &[FeaturedConfig<'static>] = &[
+    FeaturedConfig {
+        full_name: "octocat/tilemap-editor",
+        category: "Tools",
+        blurb: "The level editor of all my games since 2020",
+    },
+    FeaturedConfig {
+        full_name: "octocat/game-jam-2023",
+        category: "Games",
+        blurb: "Made in 48 hours, and still my most played game",
+    },
+    FeaturedConfig {
+        full_name: "octocat/shader-playground",
+        category: "Tools",
+        blurb: "Where shaders go before they go into a game",
+    },
+];
+
+// The languages repos are written in, most likely first.
+const LANGUAGES: &[&str] = &[
+    "Rust", "Rust", "Rust", "C++", "C++", "Lua", "C", "Python", "TypeScript", "GLSL",
+];
+// Languages that come along with the main one.
+const SIDE_LANGUAGES: &[&str] = &["Shell", "Makefile", "CMake", "HTML", "JavaScript", "Lua", "Python"];
+
+/// The time the sample stats are as of.
+pub fn now() -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(SAMPLE_NOW)
+        .expect("SAMPLE_NOW is RFC 3339")
+        .with_timezone(&Utc)
+}
+
+/// The sample repos, as a forge would list them.
+pub fn repos() -> Vec<Repo> {
+    let mut rng = Lcg(SAMPLE_SEED);
+    REPOS
+        .iter()
+        .enumerate()
+        .map(|(i, (name, description))| {
+            // Most repos have a handful of stars, a few have many.
+            let stargazer_count = (rng.below(40) * rng.below(40) / (1 + rng.below(10))) as i64;
+            let pushed_at = now() - Duration::days(rng.below(1500) as i64) - Duration::minutes(rng.below(1440) as i64);
+            // Created some time between the account and the last push.
+            let created_at = pushed_at - Duration::days(rng.below((pushed_at - created_at()).num_days() as u64 + 1) as i64);
+            let main = LANGUAGES[rng.below(LANGUAGES.len() as u64) as usize];
+            let mut languages = vec![Language {
+                name: main.to_string(),
+                color: None,
+                bytes: 5_000 + rng.below(400_000) as i64,
+            }];
+            for _ in 0..rng.below(3) {
+                let side = SIDE_LANGUAGES[rng.below(SIDE_LANGUAGES.len() as u64) as usize];
+                if languages.iter().all(|l| l.name != side) {
+                    languages.push(Language {
+                        name: side.to_string(),
+                        color: None,
+                        bytes: 200 + rng.below(20_000) as i64,
+                    });
+                }
+            }
+            Repo {
+                full_name: format!("{SAMPLE_OWNER}/{name}"),
+                url: format!("https://github.com/{SAMPLE_OWNER}/{name}"),
+                stargazer_count,
+                fork_count: stargazer_count / (2 + rng.below(6) as i64),
+                created_at: Some(created_at.to_rfc3339()),
+                pushed_at: Some(pushed_at.to_rfc3339()),
+                recent_commits: vec![],
+                last_commit_at: Some(pushed_at.to_rfc3339()),
+                description: Some(description.to_string()).filter(|d| !d.is_empty()),
+                homepage_url: (i % 5 == 0).then(|| format!("https://{SAMPLE_OWNER}.github.io/{name}")),
+                primary_language: Some(main.to_string()),
+                open_graph_image_url: Some(format!("https://opengraph.githubassets.com/1/{SAMPLE_OWNER}/{name}")),
+                languages,
+                is_archived: i == REPOS.len() - 4,
+                is_disabled: false,
+                is_empty: false,
+                is_fork: i % 7 == 6,
+                is_mirror: false,
+                is_private: i == REPOS.len() - 1,
+            }
+        })
+        .collect()
+}
+
+pub fn user_and_repo_stats() -> UserAndRepoStats {
+    let mut stats = aggregate::collect_user_repo_stats(&repos(), now(), Weighting::Bytes)
+        .expect("the sample repos have valid push times");
+    stats.created_at = SAMPLE_CREATED_AT.to_string();
+    stats
+}
+
+fn created_at() -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(SAMPLE_CREATED_AT)
+        .expect("SAMPLE_CREATED_AT is RFC 3339")
+        .with_timezone(&Utc)
+}
+
+pub fn featured() -> &'static [FeaturedConfig<'static>] {
+    FEATURED
+}
+
+pub fn issue_and_pr_stats() -> IssueAndPrStats {
+    IssueAndPrStats {
+        issues_created: 87,
+        issues_closed: 64,
+        prs_created: 153,
+        prs_merged: 131,
+    }
+}
+
+/// When the stars of the sample repos were given, more of them lately.
+pub fn star_history() -> StarHistory {
+    let mut rng = Lcg(SAMPLE_SEED);
+    let repos = repos()
+        .into_iter()
+        .filter(|r| !(r.is_archived || r.is_disabled || r.is_empty || r.is_private) && r.stargazer_count > 0)
+        .filter(|r| !(r.is_fork || r.is_mirror))
+        .map(|repo| {
+            let mut starred_on: Vec<String> = (0..repo.stargazer_count)
+                .map(|_| {
+                    // The smaller of two rolls, so recent days come up more.
+                    let days_ago = rng.below(SAMPLE_STAR_DAYS).min(rng.below(SAMPLE_STAR_DAYS));
+                    (now() - Duration::days(days_ago as i64)).format("%Y-%m-%d").to_string()
+                })
+                .collect();
+            starred_on.sort();
+            RepoStars {
+                full_name: repo.full_name,
+                url: repo.url,
+                starred_on,
+            }
+        })
+        .collect();
+    StarHistory { repos }
+}
+
+// A tiny linear congruential generator (Knuth's MMIX constants), which is
+// plenty for made-up stats and keeps them the same on every platform.
+struct Lcg(u64);
+
+impl Lcg {
+    fn below(&mut self, n: u64) -> u64 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        (self.0 >> 33) % n
+    }
+}
@@ -0,0 +1,351 @@
+// What templates can use: every field of the README context with its type and
+// what it means. The sample context must have exactly these fields, which
+// the tests check, so the list can't silently go stale.
+use std::fmt::Write;
+
+// The fields as `(path, type, description)`. In paths, `[]` stands for the
+// elements of a list or the values of a map. Types ending in `?` can be null,
+// e.g. when the data source of a section failed.
+const FIELDS: &[(&str, &str, &str)] = &[
+    ("user_and_repo_stats", "object?", "Account and repo totals; null when they couldn't be fetched"),
+    ("user_and_repo_stats.created_at", "string", "When the oldest account was created, as RFC 3339"),
+    ("user_and_repo_stats.total_repos", "integer", "Listed repos, i.e. not archived, disabled, empty or private"),
+    ("user_and_repo_stats.owned_repos", "integer", "Listed repos that are neither forks nor mirrors"),
+    ("user_and_repo_stats.forked_repos", "integer", "Listed forks and unmerged mirrors"),
+    ("user_and_repo_stats.live_repos", "integer", "Owned repos with commits in the last two years, not counting bot commits where the forge lists them"),
+    ("user_and_repo_stats.total_stars", "integer", "Stars of all owned repos"),
+    ("user_and_repo_stats.total_forks", "integer", "Forks of all owned repos"),
+    ("user_and_repo_stats.all_time_languages", "map of object", "Language totals of all owned repos, by language name"),
+    ("user_and_repo_stats.all_time_languages[].color", "string", "The language's color, e.g. `#dea584`"),
+    ("user_and_repo_stats.all_time_languages[].bytes", "integer", "Bytes of code"),
+    ("user_and_repo_stats.all_time_languages[].weight", "number", "The bytes, weighted by the language weighting"),
+    ("user_and_repo_stats.recent_languages", "map of object", "Language totals of the live repos, by language name"),
+    ("user_and_repo_stats.recent_languages[].color", "string", "The language's color"),
+    ("user_and_repo_stats.recent_languages[].bytes", "integer", "Bytes of code"),
+    ("user_and_repo_stats.recent_languages[].weight", "number", "The bytes, weighted by the language weighting"),
+    ("user_and_repo_stats.language_weighting", "string", "How languages are weighted, e.g. `bytes` or `stars`"),
+    ("user_and_repo_stats.languages_weighted", "bool", "Whether the weighting is anything but plain bytes"),
+    ("user_and_repo_stats.languages_by_year", "map of map of object", "Language totals of all owned repos, by year"),
+    ("user_and_repo_stats.languages_by_year[]", "map of object", "The year's language totals, by language name"),
+    ("user_and_repo_stats.languages_by_year[][].color", "string", "The language's color"),
+    ("user_and_repo_stats.languages_by_year[][].bytes", "integer", "Bytes of code"),
+    ("user_and_repo_stats.languages_by_year[][].weight", "number", "The bytes, weighted by the language weighting"),
+    ("user_and_repo_stats.languages_by_year_of", "string", "Which year of a repo counts, `creation` or `last push`"),
+    ("user_and_repo_stats.repos", "list of object", "The live repos"),
+    ("user_and_repo_stats.repos[].full_name", "string", "`owner/name`"),
+    ("user_and_repo_stats.repos[].url", "string", "The repo's web page"),
+    ("user_and_repo_stats.repos[].stargazer_count", "integer", "Stars"),
+    ("user_and_repo_stats.repos[].fork_count", "integer", "Forks"),
+    ("user_and_repo_stats.repos[].pushed_date", "string", "The day of the last push, as YYYY-MM-DD. Bot commits and pushes to other branches don't count where the forge tells"),
+    ("user_and_repo_stats.repos[].created_date", "string", "The day the repo was created, as YYYY-MM-DD; empty if unknown"),
+    ("user_and_repo_stats.repos[].age_years", "integer", "Full years since the repo was created"),
+    ("user_and_repo_stats.repos[].description", "string?", "On one line, cut to a maximum length"),
+    ("user_and_repo_stats.repos[].homepage_url", "string?", "The repo's homepage, if it has one"),
+    ("user_and_repo_stats.repos[].primary_language", "object?", "The main language, or the largest one"),
+    ("user_and_repo_stats.repos[].primary_language.name", "string", "The language"),
+    ("user_and_repo_stats.repos[].primary_language.color", "string", "The language's color"),
+    ("user_and_repo_stats.repos[].open_graph_image_url", "string?", "The image shown when the repo is linked; GitHub only"),
+    ("user_and_repo_stats.dormant_repos", "list of object", "The owned repos that aren't live, like `user_and_repo_stats.repos`"),
+    ("user_and_repo_stats.dormant_repos[].full_name", "string", "`owner/name`"),
+    ("user_and_repo_stats.dormant_repos[].url", "string", "The repo's web page"),
+    ("user_and_repo_stats.dormant_repos[].stargazer_count", "integer", "Stars"),
+    ("user_and_repo_stats.dormant_repos[].fork_count", "integer", "Forks"),
+    ("user_and_repo_stats.dormant_repos[].pushed_date", "string", "The day of the last push, as YYYY-MM-DD; empty if unknown"),
+    ("user_and_repo_stats.dormant_repos[].created_date", "string", "The day the repo was created, as YYYY-MM-DD; empty if unknown"),
+    ("user_and_repo_stats.dormant_repos[].age_years", "integer", "Full years since the repo was created"),
+    ("user_and_repo_stats.dormant_repos[].description", "string?", "On one line, cut to a maximum length"),
+    ("user_and_repo_stats.dormant_repos[].homepage_url", "string?", "The repo's homepage, if it has one"),
+    ("user_and_repo_stats.dormant_repos[].primary_language", "object?", "The main language, or the largest one"),
+    ("user_and_repo_stats.dormant_repos[].primary_language.name", "string", "The language"),
+    ("user_and_repo_stats.dormant_repos[].primary_language.color", "string", "The language's color"),
+    ("user_and_repo_stats.dormant_repos[].open_graph_image_url", "string?", "The image shown when the repo is linked; GitHub only"),
+    ("user_and_repo_stats.archived_repos", "list of string", "The owned public repos that are archived, as `owner/name`"),
+    ("user_and_repo_stats.repos_by_year", "list of object", "Every year since the first owned repo, oldest first"),
+    ("user_and_repo_stats.repos_by_year[].year", "integer", "The year, e.g. `2024`"),
+    ("user_and_repo_stats.repos_by_year[].created", "integer", "Owned repos created in the year"),
+    ("user_and_repo_stats.repos_by_year[].pushed", "integer", "Owned repos last pushed to in the year"),
+    ("top_repos", "object?", "The live repos in README order; null with `user_and_repo_stats`"),
+    ("top_repos.most_recent", "list of object", "The most recently pushed, like `user_and_repo_stats.repos`"),
+    ("top_repos.most_recent[].full_name", "string", "`owner/name`"),
+    ("top_repos.most_recent[].url", "string", "The repo's web page"),
+    ("top_repos.most_recent[].stargazer_count", "integer", "Stars"),
+    ("top_repos.most_recent[].fork_count", "integer", "Forks"),
+    ("top_repos.most_recent[].pushed_date", "string", "The day of the last push, as YYYY-MM-DD. Bot commits and pushes to other branches don't count where the forge tells"),
+    ("top_repos.most_recent[].created_date", "string", "The day the repo was created, as YYYY-MM-DD; empty if unknown"),
+    ("top_repos.most_recent[].age_years", "integer", "Full years since the repo was created"),
+    ("top_repos.most_recent[].description", "string?", "On one line, cut to a maximum length"),
+    ("top_repos.most_recent[].homepage_url", "string?", "The repo's homepage, if it has one"),
+    ("top_repos.most_recent[].primary_language", "object?", "The main language, or the largest one"),
+    ("top_repos.most_recent[].primary_language.name", "string", "The language"),
+    ("top_repos.most_recent[].primary_language.color", "string", "The language's color"),
+    ("top_repos.most_recent[].open_graph_image_url", "string?", "The image shown when the repo is linked; GitHub only"),
+    ("top_repos.most_starred", "list of object", "The most starred"),
+    ("top_repos.most_starred[].full_name", "string", "`owner/name`"),
+    ("top_repos.most_starred[].url", "string", "The repo's web page"),
+    ("top_repos.most_starred[].stargazer_count", "integer", "Stars"),
+    ("top_repos.most_starred[].fork_count", "integer", "Forks"),
+    ("top_repos.most_starred[].pushed_date", "string", "The day of the last push, as YYYY-MM-DD. Bot commits and pushes to other branches don't count where the forge tells"),
+    ("top_repos.most_starred[].created_date", "string", "The day the repo was created, as YYYY-MM-DD; empty if unknown"),
+    ("top_repos.most_starred[].age_years", "integer", "Full years since the repo was created"),
+    ("top_repos.most_starred[].description", "string?", "On one line, cut to a maximum length"),
+    ("top_repos.most_starred[].homepage_url", "string?", "The repo's homepage, if it has one"),
+    ("top_repos.most_starred[].primary_language", "object?", "The main language, or the largest one"),
+    ("top_repos.most_starred[].primary_language.name", "string", "The language"),
+    ("top_repos.most_starred[].primary_language.color", "string", "The language's color"),
+    ("top_repos.most_starred[].open_graph_image_url", "string?", "The image shown when the repo is linked; GitHub only"),
+    ("top_repos.most_forked", "list of object", "The most forked, of those forked at all"),
+    ("top_repos.most_forked[].full_name", "string", "`owner/name`"),
+    ("top_repos.most_forked[].url", "string", "The repo's web page"),
+    ("top_repos.most_forked[].stargazer_count", "integer", "Stars"),
+    ("top_repos.most_forked[].fork_count", "integer", "Forks"),
+    ("top_repos.most_forked[].pushed_date", "string", "The day of the last push, as YYYY-MM-DD. Bot commits and pushes to other branches don't count where the forge tells"),
+    ("top_repos.most_forked[].created_date", "string", "The day the repo was created, as YYYY-MM-DD; empty if unknown"),
+    ("top_repos.most_forked[].age_years", "integer", "Full years since the repo was created"),
+    ("top_repos.most_forked[].description", "string?", "On one line, cut to a maximum length"),
+    ("top_repos.most_forked[].homepage_url", "string?", "The repo's homepage, if it has one"),
+    ("top_repos.most_forked[].primary_language", "object?", "The main language, or the largest one"),
+    ("top_repos.most_forked[].primary_language.name", "string", "The language"),
+    ("top_repos.most_forked[].primary_language.color", "string", "The language's color"),
+    ("top_repos.most_forked[].open_graph_image_url", "string?", "The image shown when the repo is linked; GitHub only"),
+    ("featured", "list of object?", "The repos to show off from the config, by category; null with `user_and_repo_stats`"),
+    ("featured[].name", "string", "The category, or empty for repos without one"),
+    ("featured[].repos", "list of object", "The category's repos, in config order"),
+    ("featured[].repos[].blurb", "string", "The line about the repo from the config"),
+    ("featured[].repos[].repo", "object", "The repo, like `user_and_repo_stats.repos`"),
+    ("featured[].repos[].repo.full_name", "string", "`owner/name`"),
+    ("featured[].repos[].repo.url", "string", "The repo's web page"),
+    ("featured[].repos[].repo.stargazer_count", "integer", "Stars"),
+    ("featured[].repos[].repo.fork_count", "integer", "Forks"),
+    ("featured[].repos[].repo.pushed_date", "string", "The day of the last push, as YYYY-MM-DD. Bot commits and pushes to other branches don't count where the forge tells"),
+    ("featured[].repos[].repo.created_date", "string", "The day the repo was created, as YYYY-MM-DD; empty if unknown"),
+    ("featured[].repos[].repo.age_years", "integer", "Full years since the repo was created"),
+    ("featured[].repos[].repo.description", "string?", "On one line, cut to a maximum length"),
+    ("featured[].repos[].repo.homepage_url", "string?", "The repo's homepage, if it has one"),
+    ("featured[].repos[].repo.primary_language", "object?", "The main language, or the largest one"),
+    ("featured[].repos[].repo.primary_language.name", "string", "The language"),
+    ("featured[].repos[].repo.primary_language.color", "string", "The language's color"),
+    ("featured[].repos[].repo.open_graph_image_url", "string?", "The image shown when the repo is linked; GitHub only"),
+    ("issue_and_pr_stats", "object?", "Issue and pull request counts; null when they couldn't be fetched"),
+    ("issue_and_pr_stats.issues_created", "integer", "Issues opened"),
+    ("issue_and_pr_stats.issues_closed", "integer", "Of those, the closed ones"),
+    ("issue_and_pr_stats.prs_created", "integer", "Pull and merge requests opened"),
+    ("issue_and_pr_stats.prs_merged", "integer", "Of those, the merged ones"),
+    ("top_all_time_languages", "list of object?", "The largest languages of all owned repos, then `Other`"),
+    ("top_all_time_languages[].name", "string", "The language, or `Other`"),
+    ("top_all_time_languages[].color", "string", "The language's color"),
+    ("top_all_time_languages[].percentage", "integer", "Share of the weighted total; all of them add up to 100"),
+    ("top_all_time_languages[].raw_percentage", "integer", "Share of the plain byte total"),
+    ("top_all_time_languages[].bytes", "string", "Bytes of code, e.g. `1.2 MiB`"),
+    ("top_all_time_languages[].members", "list of string", "The languages in `Other`; empty for all others"),
+    ("top_recent_languages", "list of object?", "The same for the live repos"),
+    ("top_recent_languages[].name", "string", "The language, or `Other`"),
+    ("top_recent_languages[].color", "string", "The language's color"),
+    ("top_recent_languages[].percentage", "integer", "Share of the weighted total; all of them add up to 100"),
+    ("top_recent_languages[].raw_percentage", "integer", "Share of the plain byte total"),
+    ("top_recent_languages[].bytes", "string", "Bytes of code, e.g. `1.2 MiB`"),
+    ("top_recent_languages[].members", "list of string", "The languages in `Other`; empty for all others"),
+    ("top_all_time_language_groups", "list of object?", "Like `top_all_time_languages`, with language groups as one"),
+    ("top_all_time_language_groups[].name", "string", "The language or group, or `Other`"),
+    ("top_all_time_language_groups[].color", "string", "The language's or group's color"),
+    ("top_all_time_language_groups[].percentage", "integer", "Share of the weighted total; all of them add up to 100"),
+    ("top_all_time_language_groups[].raw_percentage", "integer", "Share of the plain byte total"),
+    ("top_all_time_language_groups[].bytes", "string", "Bytes of code, e.g. `1.2 MiB`"),
+    ("top_all_time_language_groups[].members", "list of string", "The languages and groups in `Other`"),
+    ("top_recent_language_groups", "list of object?", "Like `top_recent_languages`, with language groups as one"),
+    ("top_recent_language_groups[].name", "string", "The language or group, or `Other`"),
+    ("top_recent_language_groups[].color", "string", "The language's or group's color"),
+    ("top_recent_language_groups[].percentage", "integer", "Share of the weighted total; all of them add up to 100"),
+    ("top_recent_language_groups[].raw_percentage", "integer", "Share of the plain byte total"),
+    ("top_recent_language_groups[].bytes", "string", "Bytes of code, e.g. `1.2 MiB`"),
+    ("top_recent_language_groups[].members", "list of string", "The languages and groups in `Other`"),
+    ("language_evolution", "list of object?", "The top languages of each year, oldest first"),
+    ("language_evolution[].year", "integer", "The year, e.g. `2024`"),
+    ("language_evolution[].languages", "list of object", "Like `top_all_time_languages`, for the year's repos"),
+    ("language_evolution[].languages[].name", "string", "The language, or `Other`"),
+    ("language_evolution[].languages[].color", "string", "The language's color"),
+    ("language_evolution[].languages[].percentage", "integer", "Share of the year's weighted total"),
+    ("language_evolution[].languages[].raw_percentage", "integer", "Share of the year's plain byte total"),
+    ("language_evolution[].languages[].bytes", "string", "Bytes of code, e.g. `1.2 MiB`"),
+    ("language_evolution[].languages[].members", "list of string", "The languages in `Other`"),
+    ("push_activity", "list of object?", "Live repos by the month of their last push, oldest first"),
+    ("push_activity[].month", "string", "As YYYY-MM"),
+    ("push_activity[].repos", "integer", "Repos last pushed to in the month"),
+    ("timeline", "object?", "Account and repo ages; null with `user_and_repo_stats`"),
+    ("timeline.account_created_date", "string?", "When the oldest account was created, as YYYY-MM-DD"),
+    ("timeline.account_age_years", "integer?", "Full years since the oldest account was created"),
+    ("timeline.oldest_active_repo", "object?", "The live repo created first, like `user_and_repo_stats.repos`"),
+    ("timeline.oldest_active_repo.full_name", "string", "`owner/name`"),
+    ("timeline.oldest_active_repo.url", "string", "The repo's web page"),
+    ("timeline.oldest_active_repo.stargazer_count", "integer", "Stars"),
+    ("timeline.oldest_active_repo.fork_count", "integer", "Forks"),
+    ("timeline.oldest_active_repo.pushed_date", "string", "The day of the last push, as YYYY-MM-DD. Bot commits and pushes to other branches don't count where the forge tells"),
+    ("timeline.oldest_active_repo.created_date", "string", "The day the repo was created, as YYYY-MM-DD"),
+    ("timeline.oldest_active_repo.age_years", "integer", "Full years since the repo was created"),
+    ("timeline.oldest_active_repo.description", "string?", "On one line, cut to a maximum length"),
+    ("timeline.oldest_active_repo.homepage_url", "string?", "The repo's homepage, if it has one"),
+    ("timeline.oldest_active_repo.primary_language", "object?", "The main language, or the largest one"),
+    ("timeline.oldest_active_repo.primary_language.name", "string", "The language"),
+    ("timeline.oldest_active_repo.primary_language.color", "string", "The language's color"),
+    ("timeline.oldest_active_repo.open_graph_image_url", "string?", "The image shown when the repo is linked; GitHub only"),
+    ("star_history", "object?", "Stars over time; null when they couldn't be fetched"),
+    ("star_history.total", "list of object", "The total star count on every day a star was given"),
+    ("star_history.total[].date", "string", "As YYYY-MM-DD"),
+    ("star_history.total[].stars", "integer", "Stars at the end of the day"),
+    ("star_history.gained_last_30_days", "integer", "Stars given in the last 30 days"),
+    ("star_history.gained_last_365_days", "integer", "Stars given in the last 365 days"),
+    ("star_history.repos", "list of object", "Every repo with stars, most starred first"),
+    ("star_history.repos[].full_name", "string", "`owner/name`"),
+    ("star_history.repos[].url", "string", "The repo's web page"),
+    ("star_history.repos[].stars", "integer", "Stars"),
+    ("star_history.repos[].gained_last_30_days", "integer", "Stars given in the last 30 days"),
+    ("star_history.repos[].gained_last_365_days", "integer", "Stars given in the last 365 days"),
+    ("star_history.repos[].series", "list of object", "The repo's star count on every day it got a star"),
+    ("star_history.repos[].series[].date", "string", "As YYYY-MM-DD"),
+    ("star_history.repos[].series[].stars", "integer", "Stars at the end of the day"),
+    ("star_history.fastest_growing", "list of object", "The repos with the most stars in the last 30 days, then 365"),
+    ("star_history.fastest_growing[].full_name", "string", "`owner/name`"),
+    ("star_history.fastest_growing[].url", "string", "The repo's web page"),
+    ("star_history.fastest_growing[].stars", "integer", "Stars"),
+    ("star_history.fastest_growing[].gained_last_30_days", "integer", "Stars given in the last 30 days"),
+    ("star_history.fastest_growing[].gained_last_365_days", "integer", "Stars given in the last 365 days"),
+    ("star_history.fastest_growing[].series", "list of object", "The repo's star count on every day it got a star"),
+    ("star_history.fastest_growing[].series[].date", "string", "As YYYY-MM-DD"),
+    ("star_history.fastest_growing[].series[].stars", "integer", "Stars at the end of the day"),
+    ("sources", "object", "Whether each data source was fetched"),
+    ("sources.user_and_repo_stats", "object", "For `user_and_repo_stats` and what's built from it"),
+    ("sources.user_and_repo_stats.snapshot_date", "string?", "Set when the data is from the snapshot of this day"),
+    ("sources.user_and_repo_stats.unavailable", "bool", "Set when there's no data at all"),
+    ("sources.issue_and_pr_stats", "object", "For `issue_and_pr_stats`"),
+    ("sources.issue_and_pr_stats.snapshot_date", "string?", "Set when the data is from the snapshot of this day"),
+    ("sources.issue_and_pr_stats.unavailable", "bool", "Set when there's no data at all"),
+    ("sources.star_history", "object", "For `star_history`"),
+    ("sources.star_history.snapshot_date", "string?", "Set when the data is from the snapshot of this day"),
+    ("sources.star_history.unavailable", "bool", "Set when there's no data at all"),
+    ("mermaid", "object", "Mermaid charts as fenced blocks; use them `| unescaped`"),
+    ("mermaid.recent_languages", "string?", "Pie chart of `top_recent_languages`"),
+    ("mermaid.all_time_languages", "string?", "Pie chart of `top_all_time_languages`"),
+    ("mermaid.recent_language_groups", "string?", "Pie chart of `top_recent_language_groups`"),
+    ("mermaid.all_time_language_groups", "string?", "Pie chart of `top_all_time_language_groups`"),
+    ("mermaid.push_activity", "string?", "Bar chart of `push_activity`"),
+    ("mermaid.repos_by_year", "string?", "Bars and a line of `user_and_repo_stats.repos_by_year`"),
+    ("mermaid.issues_and_prs", "string?", "Bar chart of `issue_and_pr_stats`"),
+];
+
+/// The fields of the README context as an indented tree, one per line, e.g.
+/// `  total_stars: integer - Stars of all owned repos`.
+pub fn tree() -> String {
+    let mut tree = String::new();
+    for (path, kind, description) in FIELDS {
+        let depth = path.matches('.').count();
+        let name = path.rsplit('.').next().unwrap_or(path);
+        let _ = writeln!(tree, "{}{name}: {kind} - {description}", "  ".repeat(depth));
+    }
+    tree
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{sample, Context, LanguageGroupTotals, Sources};
+    use serde_json::Value;
+    use std::collections::BTreeMap;
+
+    fn declared(path: &str) -> Option<&'static str> {
+        FIELDS.iter().find(|(p, _, _)| *p == path).map(|(_, kind, _)| *kind)
+    }
+
+    fn kind(value: &Value) -> String {
+        match value {
+            Value::Null => "null".to_string(),
+            Value::Bool(_) => "bool".to_string(),
+            Value::Number(n) if n.is_i64() => "integer".to_string(),
+            Value::Number(_) => "number".to_string(),
+            Value::String(_) => "string".to_string(),
+            Value::Array(a) => a.first().map_or("list".to_string(), |v| format!("list of {}", kind(v))),
+            Value::Object(_) => "object".to_string(),
+        }
+    }
+
+    // The type of every field in `value`, with the elements of all lists and
+    // maps merged.
+    fn collect(path: &str, value: &Value, fields: &mut BTreeMap<String, String>) {
+        // Elements are described by their list or map.
+        if path.ends_with("[]") && !value.is_object() {
+            return;
+        }
+        let join = |key: &str| if path.is_empty() { key.to_string() } else { format!("{path}.{key}") };
+        match value {
+            Value::Object(map) if declared(path).is_some_and(|k| k.starts_with("map")) => {
+                // Maps of maps, as JSON objects, can only be told apart by
+                // what's declared.
+                let first = match declared(&format!("{path}[]")) {
+                    Some(k) if k.starts_with("map") => k.to_string(),
+                    _ => map.values().next().map_or(String::new(), kind),
+                };
+                fields.insert(path.to_string(), format!("map of {first}"));
+                for value in map.values() {
+                    collect(&format!("{path}[]"), value, fields);
+                }
+            }
+            Value::Object(map) => {
+                if !path.is_empty() && !path.ends_with("[]") {
+                    fields.insert(path.to_string(), "object".to_string());
+                }
+                for (key, value) in map {
+                    collect(&join(key), value, fields);
+                }
+            }
+            Value::Array(list) => {
+                let entry = fields.entry(path.to_string()).or_insert_with(|| kind(value));
+                if !list.is_empty() {
+                    *entry = kind(value);
+                }
+                for value in list {
+                    collect(&format!("{path}[]"), value, fields);
+                }
+            }
+            _ => {
+                fields.insert(path.to_string(), kind(value));
+            }
+        }
+    }
+
+    #[test]
+    fn fields_match_the_sample_context() {
+        let stats = sample::user_and_repo_stats();
+        let groups = LanguageGroupTotals::new(&stats);
+        let activity = sample::issue_and_pr_stats();
+        let star_history = sample::star_history();
+        let context = Context::new(
+            Some(&stats),
+            Some(&groups),
+            sample::featured(),
+            Some(&activity),
+            Some(&star_history),
+            Sources::default(),
+            sample::now(),
+        );
+        let mut fields = BTreeMap::new();
+        collect("", &crate::readme_context_json(&context).unwrap(), &mut fields);
+
+        for (path, found) in &fields {
+            let declared = declared(path).unwrap_or_else(|| panic!("{path} is missing from FIELDS"));
+            let optional = declared.ends_with('?');
+            let declared = declared.trim_end_matches('?');
+            assert!(
+                found == declared || (found == "null" && optional) || (found == "list" && declared.starts_with("list")),
+                "{path} is a {found}, but FIELDS says {declared}"
+            );
+        }
+        for (path, _, _) in FIELDS {
+            assert!(fields.contains_key(*path), "{path} is in FIELDS, but not in the context");
+        }
+    }
+
+    #[test]
+    fn tree_is_indented_by_depth() {
+        let tree = tree();
+        assert!(tree.starts_with("user_and_repo_stats: object? - "));
+        assert!(tree.contains("\n  repos: list of object - The live repos\n    full_name: string - `owner/name`\n"));
+    }
+}
@@ -0,0 +1,53 @@
+// The last successfully fetched data of each source, kept on disk so a
+// section can fall back to it when its source fails.
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+use crate::{IssueAndPrStats, StarHistory, UserAndRepoStats};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Snapshotted<T> {
+    pub taken_at: String,
+    pub data: T,
+}
+
+impl<T> Snapshotted<T> {
+    pub fn now(data: T) -> Self {
+        Self {
+            taken_at: Utc::now().format("%Y-%m-%d").to_string(),
+            data,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub user_and_repo_stats: Option<Snapshotted<UserAndRepoStats>>,
+    pub issue_and_pr_stats: Option<Snapshotted<IssueAndPrStats>>,
+    pub star_history: Option<Snapshotted<StarHistory>>,
+}
+
+impl Snapshot {
+    /// Loads the snapshot at `path`. A missing or unreadable snapshot is
+    /// treated as empty, since it's only ever a fallback.
+    pub fn load(path: &Path) -> Self {
+        let content = match fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) => {
+                tracing::info!("No snapshot loaded from {}: {}", path.display(), e);
+                return Self::default();
+            }
+        };
+        serde_json::from_str(&content).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring invalid snapshot {}: {}", path.display(), e);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        crate::output::write_if_changed(path, serde_json::to_string_pretty(self)?.as_bytes())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,332 @@
+// Small inline SVG charts. They don't reference anything external, so they
+// can be embedded in any page as is.
+use chrono::NaiveDate;
+use std::fmt::Write;
+
+use crate::{LanguageStat, LanguageYear, StarCount, StarGrowth};
+
+const BAR_HEIGHT: u32 = 10;
+const TRACK_COLOR: &str = "#e1e4e8";
+
+// Line charts have a fixed size, as they are images of their own.
+const CHART_WIDTH: f64 = 800.0;
+const CHART_HEIGHT: f64 = 300.0;
+// The plot area within, leaving room for the title, legend and labels.
+const PLOT_LEFT: f64 = 50.0;
+const PLOT_RIGHT: f64 = CHART_WIDTH - 20.0;
+const PLOT_TOP: f64 = 60.0;
+const PLOT_BOTTOM: f64 = CHART_HEIGHT - 30.0;
+const AXIS_COLOR: &str = "#8c959f";
+const TEXT_COLOR: &str = "#24292f";
+
+// The star history shows the total and this many of the most starred repos.
+const STAR_HISTORY_REPOS: usize = 3;
+const STAR_HISTORY_COLORS: &[&str] = &["#0969da", "#bf3989", "#1a7f37", "#9a6700"];
+
+/// Escapes text for use in SVG/XML content and attribute values.
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// A stacked horizontal bar of the language percentages, like the one on
+/// GitHub's repo pages. It scales to the width of its container.
+pub fn language_bar(languages: &[LanguageStat<'_>]) -> String {
+    let total: i64 = languages.iter().map(|l| l.percentage).sum();
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100 {BAR_HEIGHT}" preserveAspectRatio="none" width="100%" height="{BAR_HEIGHT}" role="img" aria-label="Language breakdown">"#
+    );
+    let mut x = 0.0;
+    for language in languages {
+        let width = if total > 0 {
+            language.percentage as f64 * 100.0 / total as f64
+        } else {
+            0.0
+        };
+        let _ = write!(
+            svg,
+            r#"<rect x="{x:.3}" y="0" width="{width:.3}" height="{BAR_HEIGHT}" fill="{}"><title>{} {}%</title></rect>"#,
+            escape(language.color),
+            escape(language.name),
+            language.percentage,
+        );
+        x += width;
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// A bar showing `part` as a fraction of `total`.
+pub fn ratio_bar(part: i64, total: i64, color: &str, label: &str) -> String {
+    let width = if total > 0 {
+        (part as f64 * 100.0 / total as f64).clamp(0.0, 100.0)
+    } else {
+        0.0
+    };
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 100 {BAR_HEIGHT}" preserveAspectRatio="none" width="100%" height="{BAR_HEIGHT}" role="img" aria-label="{label}"><rect x="0" y="0" width="100" height="{BAR_HEIGHT}" fill="{TRACK_COLOR}"/><rect x="0" y="0" width="{width:.3}" height="{BAR_HEIGHT}" fill="{color}"><title>{label}</title></rect></svg>"#,
+        label = escape(label),
+        color = escape(color),
+    )
+}
+
+/// A line of a [`line_chart`]: the count on each day it changed.
+pub struct Line<'a> {
+    pub label: &'a str,
+    pub color: &'a str,
+    pub points: &'a [StarCount],
+}
+
+/// A chart of counts over time, from the earliest point up to `until`. The
+/// lines step up on the days of their points.
+pub fn line_chart(title: &str, lines: &[Line<'_>], until: NaiveDate) -> String {
+    let parse = |p: &StarCount| NaiveDate::parse_from_str(&p.date, "%Y-%m-%d").ok();
+    let start = lines
+        .iter()
+        .filter_map(|l| l.points.first().and_then(parse))
+        .min()
+        .unwrap_or(until)
+        .min(until);
+    let days = (until - start).num_days().max(1) as f64;
+    let max = lines.iter().flat_map(|l| l.points).map(|p| p.stars).max().unwrap_or(0).max(1);
+    let x = |date: NaiveDate| PLOT_LEFT + (date - start).num_days() as f64 / days * (PLOT_RIGHT - PLOT_LEFT);
+    let y = |count: i64| PLOT_BOTTOM - count as f64 / max as f64 * (PLOT_BOTTOM - PLOT_TOP);
+
+    let mut svg = chart_start(title, lines.iter().map(|l| (l.label, l.color)));
+    for (count, text_y) in [(0, PLOT_BOTTOM), (max, PLOT_TOP + 4.0)] {
+        let _ = write!(
+            svg,
+            r#"<text x="{}" y="{text_y}" text-anchor="end" fill="{TEXT_COLOR}">{count}</text>"#,
+            PLOT_LEFT - 6.0
+        );
+    }
+    for (date, anchor, text_x) in [(start, "start", PLOT_LEFT), (until, "end", PLOT_RIGHT)] {
+        let _ = write!(
+            svg,
+            r#"<text x="{text_x}" y="{}" text-anchor="{anchor}" fill="{TEXT_COLOR}">{}</text>"#,
+            PLOT_BOTTOM + 18.0,
+            date.format("%Y-%m-%d")
+        );
+    }
+
+    for line in lines {
+        let points: Vec<_> = line.points.iter().filter_map(|p| Some((parse(p)?, p.stars))).collect();
+        let Some((first, _)) = points.first() else {
+            continue;
+        };
+        let mut path = format!("M{:.1} {:.1}", x(*first), y(0));
+        for (date, count) in &points {
+            let _ = write!(path, "H{:.1}V{:.1}", x(*date), y(*count));
+        }
+        let _ = write!(path, "H{:.1}", x(until));
+        let _ = write!(
+            svg,
+            r#"<path d="{path}" fill="none" stroke="{}" stroke-width="2"><title>{}</title></path>"#,
+            escape(line.color),
+            escape(line.label)
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// A band of a [`stacked_area_chart`], with a value for every year.
+pub struct Area<'a> {
+    pub label: &'a str,
+    pub color: &'a str,
+    pub values: Vec<f64>,
+}
+
+/// A chart of percentages over `years`, with the `areas` stacked on top of
+/// each other, the first one at the bottom.
+pub fn stacked_area_chart(title: &str, years: &[i32], areas: &[Area<'_>]) -> String {
+    // A single year is drawn across the whole width.
+    let columns = years.len().max(2);
+    let last = years.len().saturating_sub(1);
+    let x = |i: usize| PLOT_LEFT + i as f64 / (columns - 1) as f64 * (PLOT_RIGHT - PLOT_LEFT);
+    let y = |percentage: f64| PLOT_BOTTOM - percentage.clamp(0.0, 100.0) / 100.0 * (PLOT_BOTTOM - PLOT_TOP);
+
+    let mut svg = chart_start(title, areas.iter().map(|a| (a.label, a.color)));
+    for (percentage, text_y) in [(0, PLOT_BOTTOM), (100, PLOT_TOP + 4.0)] {
+        let _ = write!(
+            svg,
+            r#"<text x="{}" y="{text_y}" text-anchor="end" fill="{TEXT_COLOR}">{percentage}%</text>"#,
+            PLOT_LEFT - 6.0
+        );
+    }
+    // Every year fits below the axis up to about 15 of them.
+    let label_every = years.len().div_ceil(15).max(1);
+    for (i, year) in years.iter().enumerate().step_by(label_every) {
+        let anchor = match i {
+            0 => "start",
+            i if i + 1 == years.len() => "end",
+            _ => "middle",
+        };
+        let _ = write!(
+            svg,
+            r#"<text x="{:.1}" y="{}" text-anchor="{anchor}" fill="{TEXT_COLOR}">{year}</text>"#,
+            x(i),
+            PLOT_BOTTOM + 18.0
+        );
+    }
+
+    let mut below = vec![0.0; columns];
+    for area in areas {
+        let above: Vec<f64> = (0..columns)
+            .map(|i| below[i] + area.values.get(i.min(last)).copied().unwrap_or(0.0))
+            .collect();
+        let mut path = String::new();
+        for (i, top) in above.iter().enumerate() {
+            let _ = write!(path, "{}{:.1} {:.1}", if i == 0 { "M" } else { "L" }, x(i), y(*top));
+        }
+        for (i, bottom) in below.iter().enumerate().rev() {
+            let _ = write!(path, "L{:.1} {:.1}", x(i), y(*bottom));
+        }
+        let _ = write!(
+            svg,
+            r##"<path d="{path}Z" fill="{}" stroke="#ffffff" stroke-width="0.5"><title>{}</title></path>"##,
+            escape(area.color),
+            escape(area.label)
+        );
+        below = above;
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// The share of each language in every year. A language that only made the
+/// cut in some years is part of `Other` in the rest.
+pub fn language_evolution(years: &[LanguageYear<'_>]) -> String {
+    // The languages with the largest shares over all years go at the bottom,
+    // and `Other` at the top.
+    let mut totals: Vec<(&str, &str, i64, bool)> = vec![];
+    for language in years.iter().flat_map(|y| &y.languages) {
+        match totals.iter_mut().find(|(name, ..)| *name == language.name) {
+            Some(total) => total.2 += language.percentage,
+            None => totals.push((language.name, language.color, language.percentage, !language.members.is_empty())),
+        }
+    }
+    totals.sort_by(|a, b| a.3.cmp(&b.3).then(b.2.cmp(&a.2)).then(a.0.cmp(b.0)));
+    let areas: Vec<Area<'_>> = totals
+        .iter()
+        .map(|&(name, color, ..)| Area {
+            label: name,
+            color,
+            values: years
+                .iter()
+                .map(|y| y.languages.iter().find(|l| l.name == name).map_or(0.0, |l| l.percentage as f64))
+                .collect(),
+        })
+        .collect();
+    let labels: Vec<i32> = years.iter().map(|y| y.year).collect();
+    stacked_area_chart("Languages by year", &labels, &areas)
+}
+
+/// The total stars over time, and those of the most starred repos.
+pub fn star_history(growth: &StarGrowth<'_>, until: NaiveDate) -> String {
+    let mut lines = vec![Line {
+        label: "All repos",
+        color: STAR_HISTORY_COLORS[0],
+        points: &growth.total,
+    }];
+    for (repo, color) in growth.repos.iter().take(STAR_HISTORY_REPOS).zip(&STAR_HISTORY_COLORS[1..]) {
+        lines.push(Line {
+            label: repo.full_name,
+            color,
+            points: &repo.series,
+        });
+    }
+    line_chart("Star history", &lines, until)
+}
+
+// Opens a chart with a title, a legend of `(label, color)` and the axes.
+fn chart_start<'a>(title: &str, legend: impl Iterator<Item = (&'a str, &'a str)>) -> String {
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {CHART_WIDTH} {CHART_HEIGHT}" width="{CHART_WIDTH}" height="{CHART_HEIGHT}" font-family="sans-serif" font-size="12" role="img" aria-label="{title}">"#,
+        title = escape(title),
+    );
+    svg.push_str(r##"<rect width="100%" height="100%" fill="#ffffff"/>"##);
+    let _ = write!(
+        svg,
+        r#"<text x="{PLOT_LEFT}" y="20" font-size="14" font-weight="bold" fill="{TEXT_COLOR}">{}</text>"#,
+        escape(title)
+    );
+    let mut legend_x = PLOT_LEFT;
+    for (label, color) in legend {
+        let _ = write!(
+            svg,
+            r#"<rect x="{legend_x}" y="32" width="10" height="10" fill="{color}"/><text x="{}" y="41" fill="{TEXT_COLOR}">{}</text>"#,
+            legend_x + 14.0,
+            escape(label),
+            color = escape(color),
+        );
+        legend_x += 30.0 + 7.0 * label.chars().count() as f64;
+    }
+    let _ = write!(
+        svg,
+        r#"<path d="M{PLOT_LEFT} {PLOT_TOP}V{PLOT_BOTTOM}H{PLOT_RIGHT}" fill="none" stroke="{AXIS_COLOR}"/>"#
+    );
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn language_bar_stacks_segments() {
+        let languages = [
+            LanguageStat { name: "Rust", color: "#dea584", percentage: 75, raw_percentage: 75, bytes: String::new(), members: vec![] },
+            LanguageStat { name: "C<++>", color: "#f34b7d", percentage: 25, raw_percentage: 25, bytes: String::new(), members: vec![] },
+        ];
+        let svg = language_bar(&languages);
+        assert!(svg.contains(r##"<rect x="0.000" y="0" width="75.000" height="10" fill="#dea584">"##), "{svg}");
+        assert!(svg.contains(r##"<rect x="75.000" y="0" width="25.000" height="10" fill="#f34b7d">"##), "{svg}");
+        assert!(svg.contains("<title>C&lt;++&gt; 25%</title>"), "{svg}");
+    }
+
+    #[test]
+    fn line_chart_steps_up_to_the_end() {
+        let points = [
+            StarCount { date: "2024-01-01".to_string(), stars: 1 },
+            StarCount { date: "2024-01-06".to_string(), stars: 2 },
+        ];
+        let lines = [Line { label: "a/<b>", color: "#0969da", points: &points }];
+        let until = NaiveDate::from_ymd_opt(2024, 1, 11).unwrap();
+        let svg = line_chart("Stars", &lines, until);
+        assert!(svg.contains(r#"<path d="M50.0 270.0H50.0V165.0H415.0V60.0H780.0""#), "{svg}");
+        assert!(svg.contains("<title>a/&lt;b&gt;</title>"), "{svg}");
+        assert!(svg.contains(">2024-01-01</text>") && svg.contains(">2024-01-11</text>"), "{svg}");
+    }
+
+    #[test]
+    fn stacked_areas_lie_on_top_of_each_other() {
+        let areas = [
+            Area { label: "C++", color: "#f34b7d", values: vec![100.0, 25.0] },
+            Area { label: "Rust", color: "#dea584", values: vec![0.0, 75.0] },
+        ];
+        let svg = stacked_area_chart("Languages", &[2020, 2024], &areas);
+        assert!(svg.contains(r##"<path d="M50.0 60.0L780.0 217.5L780.0 270.0L50.0 270.0Z" fill="#f34b7d""##), "{svg}");
+        assert!(svg.contains(r##"<path d="M50.0 60.0L780.0 60.0L780.0 217.5L50.0 60.0Z" fill="#dea584""##), "{svg}");
+        assert!(svg.contains(">2020</text>") && svg.contains(">2024</text>"), "{svg}");
+
+        let single = stacked_area_chart("Languages", &[2024], &areas[..1]);
+        assert!(single.contains(r#"<path d="M50.0 60.0L780.0 60.0L780.0 270.0L50.0 270.0Z""#), "{single}");
+    }
+
+    #[test]
+    fn ratio_bar_handles_zero_total() {
+        assert!(ratio_bar(0, 0, "#2da44e", "0 of 0").contains(r#"width="0.000""#));
+        assert!(ratio_bar(3, 4, "#2da44e", "3 of 4").contains(r#"width="75.000""#));
+    }
+}
@@ -0,0 +1,144 @@
+// How much each repo's language bytes count toward the language stats. With
+// plain byte sums one huge old repo dominates, so the other modes trade
+// "how much code" for "how much it matters".
+use chrono::{DateTime, Utc};
+use std::{fmt, str::FromStr};
+
+use crate::forge::Repo;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Weighting {
+    /// Plain byte sums.
+    #[default]
+    Bytes,
+    /// Halves a repo's weight for every `half_life_days` since its last push.
+    /// Repos that were never pushed to don't count.
+    Recency { half_life_days: f64 },
+    /// Scales a repo's bytes by one plus its stars.
+    Stars,
+    /// Every repo counts the same, split between its languages by bytes.
+    EqualPerRepo,
+    /// Counts at most `max_bytes` of any repo, split between its languages
+    /// by bytes.
+    Capped { max_bytes: i64 },
+}
+
+impl Weighting {
+    /// What each byte of `repo` is worth, where `bytes` are the bytes of its
+    /// languages that are counted.
+    pub fn factor(&self, repo: &Repo, pushed_at: Option<DateTime<Utc>>, bytes: i64, now: DateTime<Utc>) -> f64 {
+        match *self {
+            Self::Bytes => 1.0,
+            Self::Recency { half_life_days } => match pushed_at {
+                Some(pushed_at) => {
+                    let age_days = (now - pushed_at).num_seconds().max(0) as f64 / 86_400.0;
+                    0.5_f64.powf(age_days / half_life_days)
+                }
+                None => 0.0,
+            },
+            Self::Stars => 1.0 + repo.stargazer_count.max(0) as f64,
+            Self::EqualPerRepo if bytes > 0 => 1.0 / bytes as f64,
+            Self::Capped { max_bytes } if bytes > max_bytes => max_bytes as f64 / bytes as f64,
+            Self::EqualPerRepo | Self::Capped { .. } => 1.0,
+        }
+    }
+}
+
+/// Parses `bytes`, `recency:DAYS` (the half-life), `stars`, `equal` or
+/// `cap:BYTES`.
+impl FromStr for Weighting {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let (mode, arg) = match s.split_once(':') {
+            Some((mode, arg)) => (mode, Some(arg)),
+            None => (s, None),
+        };
+        let missing = || anyhow::anyhow!("Weighting `{mode}` needs a value, as in `{mode}:N`");
+        let number = |arg: Option<&str>| -> anyhow::Result<f64> {
+            let arg = arg.ok_or_else(missing)?;
+            match arg.parse::<f64>() {
+                Ok(n) if n > 0.0 => Ok(n),
+                _ => Err(anyhow::anyhow!("Expected a positive number for `{mode}`, got `{arg}`")),
+            }
+        };
+        let whole_number = |arg: Option<&str>| -> anyhow::Result<i64> {
+            let arg = arg.ok_or_else(missing)?;
+            match arg.parse::<i64>() {
+                Ok(n) if n > 0 => Ok(n),
+                _ => Err(anyhow::anyhow!("Expected a positive whole number for `{mode}`, got `{arg}`")),
+            }
+        };
+        match (mode, arg) {
+            ("bytes", None) => Ok(Self::Bytes),
+            ("recency", arg) => Ok(Self::Recency { half_life_days: number(arg)? }),
+            ("stars", None) => Ok(Self::Stars),
+            ("equal", None) => Ok(Self::EqualPerRepo),
+            ("cap", arg) => Ok(Self::Capped { max_bytes: whole_number(arg)? }),
+            _ => Err(anyhow::anyhow!(
+                "Unknown weighting `{s}`; expected `bytes`, `recency:DAYS`, `stars`, `equal` or `cap:BYTES`"
+            )),
+        }
+    }
+}
+
+/// Describes the weighting for readers, e.g. `recency (half-life of 365 days)`.
+impl fmt::Display for Weighting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bytes => write!(f, "bytes"),
+            Self::Recency { half_life_days } => write!(f, "recency (half-life of {half_life_days} days)"),
+            Self::Stars => write!(f, "stars"),
+            Self::EqualPerRepo => write!(f, "equal per repo"),
+            Self::Capped { max_bytes } => {
+                write!(f, "bytes (capped at {} per repo)", human_bytes::human_bytes(*max_bytes as f64))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo(stars: i64) -> Repo {
+        Repo {
+            full_name: "AndreasOM/repo".to_string(),
+            stargazer_count: stars,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn factors() {
+        let now = DateTime::parse_from_rfc3339("2024-06-15T00:00:00Z").unwrap().with_timezone(&Utc);
+        let year_ago = Some(DateTime::parse_from_rfc3339("2023-06-16T00:00:00Z").unwrap().with_timezone(&Utc));
+        let recency = Weighting::Recency { half_life_days: 365.0 };
+        assert_eq!(Weighting::Bytes.factor(&repo(7), year_ago, 1000, now), 1.0);
+        assert_eq!(recency.factor(&repo(0), year_ago, 1000, now), 0.5);
+        assert_eq!(recency.factor(&repo(0), Some(now), 1000, now), 1.0);
+        assert_eq!(recency.factor(&repo(0), None, 1000, now), 0.0);
+        assert_eq!(Weighting::Stars.factor(&repo(7), None, 1000, now), 8.0);
+        assert_eq!(Weighting::EqualPerRepo.factor(&repo(0), None, 1000, now), 0.001);
+        assert_eq!(Weighting::EqualPerRepo.factor(&repo(0), None, 0, now), 1.0);
+        let capped = Weighting::Capped { max_bytes: 250 };
+        assert_eq!(capped.factor(&repo(0), None, 1000, now), 0.25);
+        assert_eq!(capped.factor(&repo(0), None, 100, now), 1.0);
+    }
+
+    #[test]
+    fn parsing() {
+        assert_eq!("bytes".parse::<Weighting>().unwrap(), Weighting::Bytes);
+        assert_eq!(
+            "recency:180".parse::<Weighting>().unwrap(),
+            Weighting::Recency { half_life_days: 180.0 }
+        );
+        assert_eq!("cap:1000000".parse::<Weighting>().unwrap(), Weighting::Capped { max_bytes: 1_000_000 });
+        assert!("recency".parse::<Weighting>().is_err());
+        assert!("recency:-1".parse::<Weighting>().is_err());
+        assert!("cap:0.5".parse::<Weighting>().is_err());
+        assert!("cap:0".parse::<Weighting>().is_err());
+        assert!("stars:2".parse::<Weighting>().is_err());
+        assert!("loudness".parse::<Weighting>().is_err());
+    }
+}